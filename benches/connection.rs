@@ -0,0 +1,90 @@
+//! Per-call latency of `jobs::list` with the client's shared connection pool,compared with building a new
+//! HTTP client for every call (what the crate did before the pool was kept inside `Client`).
+//!
+//! The server is a small local stand-in that answers every request with the same msgpack reply,
+//! so the numbers only reflect the client side cost.
+use criterion::{criterion_group,criterion_main,Criterion};
+use metasploit::client::Client;
+use metasploit::msf::jobs;
+use metasploit::value::Value;
+use std::io::{BufRead,BufReader,Read,Write};
+use std::net::{TcpListener,TcpStream};
+use std::thread;
+
+fn handle(stream:TcpStream,reply:Vec<u8>) {
+    stream.set_nodelay(true).unwrap();
+    let mut writer=stream.try_clone().unwrap();
+    let mut reader=BufReader::new(stream);
+    loop {
+        let mut length=0;
+        loop {
+            let mut line=String::new();
+            if reader.read_line(&mut line).unwrap_or(0)==0 {
+                return;
+            }
+            let line=line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            if let Some((name,value))=line.split_once(':') {
+                if name.eq_ignore_ascii_case("content-length") {
+                    length=value.trim().parse().unwrap();
+                }
+            }
+        }
+        let mut body=vec![0;length];
+        reader.read_exact(&mut body).unwrap();
+        let mut response=format!("HTTP/1.1 200 OK\r\nContent-Type: binary/message-pack\r\nContent-Length: {}\r\n\r\n",reply.len()).into_bytes();
+        response.extend_from_slice(&reply);
+        writer.write_all(&response).unwrap();
+    }
+}
+
+fn serve() -> (String,u16) {
+    let listener=TcpListener::bind("127.0.0.1:0").unwrap();
+    let port=listener.local_addr().unwrap().port();
+    let reply=rmp_serde::to_vec_named(&Value::Map(vec![
+        (Value::from("result"),Value::from("success")),
+        (Value::from("token"),Value::from("TEMPbench")),
+    ])).unwrap();
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let reply=reply.clone();
+            thread::spawn(move || handle(stream,reply));
+        }
+    });
+    (format!("http://127.0.0.1:{}/api",port),port)
+}
+
+#[cfg(not(feature="async"))]
+fn list(client:&Client) -> Value {
+    jobs::list(client.clone()).unwrap()
+}
+#[cfg(feature="async")]
+fn list(client:&Client) -> Value {
+    thread_local! {
+        static RUNTIME:tokio::runtime::Runtime=tokio::runtime::Runtime::new().unwrap();
+    }
+    RUNTIME.with(|runtime| runtime.block_on(jobs::list(client.clone())).unwrap())
+}
+
+fn per_call(c:&mut Criterion) {
+    let (url,port)=serve();
+    let client=Client::try_new("127.0.0.1",port as i32,"msf","password",false).unwrap();
+    let body=rmp_serde::to_vec(&("job.list",client.gettoken())).unwrap();
+    let mut group=c.benchmark_group("jobs_list");
+    group.bench_function("shared_client",|b| {
+        b.iter(|| list(&client))
+    });
+    group.bench_function("client_per_call",|b| {
+        b.iter(|| {
+            let http=reqwest::blocking::Client::builder().danger_accept_invalid_certs(true).build().unwrap();
+            let reply=http.post(&url).header("Content-Type","binary/message-pack").body(body.clone()).send().unwrap().bytes().unwrap();
+            rmp_serde::from_slice::<Value>(&reply).unwrap()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches,per_call);
+criterion_main!(benches);
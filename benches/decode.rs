@@ -0,0 +1,64 @@
+//! Decoding a multi-megabyte `module.exploits` reply through `Client::call`,compared with what every function did
+//! before: cloning the reply buffer,then decoding it into `T` and,when that failed,again into `MsfError`.
+//!
+//! The reply comes from an in-process transport,so the numbers only reflect decoding.Both sides pay for the
+//! transport handing out its own copy of the reply.
+use criterion::{criterion_group,criterion_main,Criterion,Throughput};
+use metasploit::client::Client;
+use metasploit::error::{Error,MsfError};
+use metasploit::transport::Transport;
+use metasploit::value::Value;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+struct Modules {
+    modules:Vec<String>,
+}
+
+#[derive(Debug)]
+struct Canned(Vec<u8>);
+
+impl Transport for Canned {
+    fn send(&self,_body:Vec<u8>) -> Result<Vec<u8>,Error> {
+        Ok(self.0.clone())
+    }
+}
+
+/// About 6 MB: 100k module names of roughly 60 bytes.
+fn module_list() -> Vec<u8> {
+    let modules=(0..100_000).map(|i| Value::from(format!("windows/smb/generated_module_with_a_long_name_{:08}",i))).collect();
+    rmp_serde::to_vec_named(&Value::Map(vec![(Value::from("modules"),Value::Array(modules))])).unwrap()
+}
+
+fn two_pass<T:serde::de::DeserializeOwned>(buf:Vec<u8>) -> Result<T,Box<dyn std::error::Error>> {
+    let new_buf=buf.clone();
+    match rmp_serde::from_read(new_buf.as_slice()) {
+        Ok(val) => Ok(val),
+        Err(_) => match rmp_serde::from_read::<_,MsfError>(new_buf.as_slice()) {
+            Ok(e) => Err(e.into()),
+            Err(e) => Err(e.into()),
+        },
+    }
+}
+
+fn decode(c:&mut Criterion) {
+    let reply=module_list();
+    let transport=Canned(reply.clone());
+    let client=Client::builder().transport(Canned(reply.clone())).token("TEST").unwrap();
+
+    let mut group=c.benchmark_group("module.exploits");
+    group.throughput(Throughput::Bytes(reply.len() as u64));
+    group.sample_size(20);
+    group.bench_function("clone and two-pass decode",|b| b.iter(|| {
+        let modules:Modules=two_pass(transport.send(vec![]).unwrap()).unwrap();
+        modules.modules.len()
+    }));
+    group.bench_function("single-pass decode",|b| b.iter(|| {
+        let modules:Modules=client.call("module.exploits",vec![]).unwrap();
+        modules.modules.len()
+    }));
+    group.finish();
+}
+
+criterion_group!(benches,decode);
+criterion_main!(benches);
@@ -0,0 +1,242 @@
+//! Recording RPC traffic to a cassette file and replaying it offline.
+//!
+//! [`RecordingTransport`] wraps another [`Transport`] (usually the client's [`HttpTransport`]) and appends every
+//! call it forwards to a cassette: the method,its parameters with tokens and the login password redacted,the raw
+//! msgpack reply (minus the tokens handed out by `auth.login` and `auth.token_generate` or listed by
+//! `auth.token_list`) and how long the server took.
+//! [`ReplayTransport`] serves the recorded replies again,in the recorded order,so a session captured against a lab
+//! can be attached to a bug report or turned into a regression test.
+//!
+//! A cassette holds one JSON object per line,with the reply as hex.
+//!
+//! ## Example
+//! ```no_run
+//! use metasploit::cassette::{RecordingTransport,ReplayTransport};
+//! use metasploit::client::Client;
+//! use metasploit::msf::sessions;
+//! use metasploit::value::Value;
+//! # #[cfg(not(feature="async"))]
+//! # fn main() -> Result<(),metasploit::error::Error> {
+//! let builder=Client::builder().host("10.0.0.2").port(55553);
+//! let recorder=RecordingTransport::create(builder.http_transport()?,"lab.cassette")?;
+//! let client=builder.transport(recorder).login("msf","password")?;
+//! let live:Value=sessions::list(client)?;
+//!
+//! let replay=ReplayTransport::open("lab.cassette")?;
+//! let client=Client::builder().transport(replay).login("msf","password")?;
+//! let replayed:Value=sessions::list(client)?;
+//! assert_eq!(live,replayed);
+//! # Ok(())
+//! # }
+//! # #[cfg(feature="async")]
+//! # fn main() {}
+//! ```
+//!
+//! [`HttpTransport`]: crate::transport::HttpTransport
+use crate::error::Error as E;
+use crate::transport::Transport;
+use crate::value::Value;
+use serde::{Deserialize,Serialize};
+use std::collections::VecDeque;
+use std::fs::{File,OpenOptions};
+use std::io::{BufRead,BufReader,Write};
+use std::path::Path;
+use std::sync::{Arc,Mutex};
+use std::time::Instant;
+#[cfg(feature="async")]
+use crate::transport::BoxFuture;
+
+/// What redacted parameters are replaced with.
+pub const REDACTED:&str="[REDACTED]";
+
+/// One recorded call.
+#[derive(Serialize,Deserialize,Debug,Clone,PartialEq)]
+pub struct Interaction {
+    pub method:String,
+    /// The parameters after the method name,with secrets replaced by [`REDACTED`].
+    pub params:Vec<Value>,
+    /// The msgpack encoded reply as the server sent it,except for redacted tokens.
+    #[serde(with="hex")]
+    pub response:Vec<u8>,
+    /// How long the call took,in milliseconds.
+    pub elapsed_ms:u64,
+}
+
+impl Interaction {
+    fn new(body:&[u8],response:Vec<u8>,started:Instant) -> Result<Self,E> {
+        let mut call=match rmpv::decode::read_value(&mut &body[..]) {
+            Ok(Value::Array(call)) if !call.is_empty() => call,
+            _ => return Err(E::TransportError("request is not an RPC call".into())),
+        };
+        let method=call.remove(0).as_str().unwrap_or_default().to_string();
+        // `auth.login` sends the username and password,every other call the token first.
+        let secrets:&[usize]=match method.as_str() {
+            "auth.login" => &[1],
+            "auth.logout" | "auth.token_add" | "auth.token_remove" => &[0,1],
+            _ => &[0],
+        };
+        for index in secrets {
+            if let Some(param)=call.get_mut(*index) {
+                *param=Value::from(REDACTED);
+            }
+        }
+        Ok(Interaction {
+            method,
+            params:call,
+            response:redact_tokens(response),
+            elapsed_ms:started.elapsed().as_millis() as u64,
+        })
+    }
+}
+
+/// Replaces the `token` of a reply (`auth.login`,`auth.token_generate`) and every entry of its `tokens`
+/// (`auth.token_list`),keeping the reply as it is when it has neither.
+fn redact_tokens(response:Vec<u8>) -> Vec<u8> {
+    let mut fields=match rmpv::decode::read_value(&mut response.as_slice()) {
+        Ok(Value::Map(fields)) => fields,
+        _ => return response,
+    };
+    let mut redacted=false;
+    for (name,value) in fields.iter_mut() {
+        match (name.as_str(),value) {
+            (Some("token"),token) => *token=Value::from(REDACTED),
+            (Some("tokens"),Value::Array(tokens)) => tokens.iter_mut().for_each(|token| *token=Value::from(REDACTED)),
+            _ => continue,
+        }
+        redacted=true;
+    }
+    if !redacted {
+        return response;
+    }
+    let mut body=Vec::new();
+    match rmpv::encode::write_value(&mut body,&Value::Map(fields)) {
+        Ok(_) => body,
+        Err(_) => response,
+    }
+}
+
+/// Reads every interaction of a cassette file.
+pub fn load<P:AsRef<Path>>(path:P) -> Result<Vec<Interaction>,E> {
+    let file=File::open(path).map_err(|e| E::TransportError(e.into()))?;
+    let mut interactions=Vec::new();
+    for line in BufReader::new(file).lines() {
+        let line=line.map_err(|e| E::TransportError(e.into()))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        interactions.push(serde_json::from_str(&line).map_err(|e| E::TransportError(e.into()))?);
+    }
+    Ok(interactions)
+}
+
+/// A transport forwarding calls to another one and appending each exchange to a cassette file.
+///
+/// Calls the inner transport fails are not recorded.Clones append to the same file.
+#[derive(Debug,Clone)]
+pub struct RecordingTransport<T> {
+    inner:T,
+    file:Arc<Mutex<File>>,
+}
+
+impl<T:Transport> RecordingTransport<T> {
+    /// Records into a new cassette,truncating `path` if it exists.
+    pub fn create<P:AsRef<Path>>(inner:T,path:P) -> Result<Self,E> {
+        let file=File::create(path).map_err(|e| E::TransportError(e.into()))?;
+        Ok(RecordingTransport{inner,file:Arc::new(Mutex::new(file))})
+    }
+    /// Records at the end of an existing cassette,creating it if needed.
+    pub fn append<P:AsRef<Path>>(inner:T,path:P) -> Result<Self,E> {
+        let file=OpenOptions::new().create(true).append(true).open(path).map_err(|e| E::TransportError(e.into()))?;
+        Ok(RecordingTransport{inner,file:Arc::new(Mutex::new(file))})
+    }
+    /// The transport calls are forwarded to.
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+    fn record(&self,interaction:Interaction) -> Result<(),E> {
+        let mut line=serde_json::to_vec(&interaction).map_err(|e| E::TransportError(e.into()))?;
+        line.push(b'\n');
+        let mut file=self.file.lock().unwrap_or_else(|e| e.into_inner());
+        file.write_all(&line).and_then(|_| file.flush()).map_err(|e| E::TransportError(e.into()))
+    }
+}
+
+impl<T:Transport> Transport for RecordingTransport<T> {
+    fn send(&self,body:Vec<u8>) -> Result<Vec<u8>,E> {
+        let started=Instant::now();
+        let response=self.inner.send(body.clone())?;
+        self.record(Interaction::new(&body,response.clone(),started)?)?;
+        Ok(response)
+    }
+    #[cfg(feature="async")]
+    fn send_async(&self,body:Vec<u8>) -> BoxFuture<'_,Result<Vec<u8>,E>> {
+        Box::pin(async move {
+            let started=Instant::now();
+            let response=self.inner.send_async(body.clone()).await?;
+            self.record(Interaction::new(&body,response.clone(),started)?)?;
+            Ok(response)
+        })
+    }
+}
+
+/// A transport answering with the replies of a cassette.
+///
+/// Replies are served in the recorded order,and each call must be for the method that was recorded next,otherwise
+/// it fails with `TransportError`.Parameters are not compared,as tokens are redacted.Clones share the position in
+/// the cassette.
+#[derive(Debug,Clone)]
+pub struct ReplayTransport {
+    interactions:Arc<Mutex<VecDeque<Interaction>>>,
+}
+
+impl ReplayTransport {
+    /// Replays the cassette at `path`.
+    pub fn open<P:AsRef<Path>>(path:P) -> Result<Self,E> {
+        Ok(ReplayTransport::new(load(path)?))
+    }
+    pub fn new(interactions:Vec<Interaction>) -> Self {
+        ReplayTransport{interactions:Arc::new(Mutex::new(interactions.into()))}
+    }
+    /// How many recorded replies have not been served yet.
+    pub fn remaining(&self) -> usize {
+        self.interactions.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+}
+
+impl Transport for ReplayTransport {
+    fn send(&self,body:Vec<u8>) -> Result<Vec<u8>,E> {
+        let method=match rmpv::decode::read_value(&mut body.as_slice()) {
+            Ok(Value::Array(call)) if !call.is_empty() => call[0].as_str().unwrap_or_default().to_string(),
+            _ => return Err(E::TransportError("request is not an RPC call".into())),
+        };
+        let mut interactions=self.interactions.lock().unwrap_or_else(|e| e.into_inner());
+        match interactions.front() {
+            Some(next) if next.method==method => Ok(interactions.pop_front().unwrap().response),
+            Some(next) => Err(E::TransportError(format!("cassette expected a call to {},got {}",next.method,method).into())),
+            None => Err(E::TransportError(format!("cassette has no reply left for {}",method).into())),
+        }
+    }
+}
+
+mod hex {
+    use serde::{Deserialize,Deserializer,Serializer,de::Error};
+    use std::fmt::Write;
+
+    pub fn serialize<S:Serializer>(bytes:&[u8],serializer:S) -> Result<S::Ok,S::Error> {
+        let mut hex=String::with_capacity(bytes.len()*2);
+        for byte in bytes {
+            write!(hex,"{:02x}",byte).unwrap();
+        }
+        serializer.serialize_str(&hex)
+    }
+
+    pub fn deserialize<'de,D:Deserializer<'de>>(deserializer:D) -> Result<Vec<u8>,D::Error> {
+        let hex=String::deserialize(deserializer)?;
+        if hex.len()%2!=0 {
+            return Err(D::Error::custom("odd number of hex digits"));
+        }
+        (0..hex.len()).step_by(2).map(|i| {
+            u8::from_str_radix(hex.get(i..i+2).unwrap_or_default(),16).map_err(D::Error::custom)
+        }).collect()
+    }
+}
@@ -1,63 +1,546 @@
-#[path="./connect.rs"] mod connect;
-#[path="./structs/mod.rs"] mod structs;
-use structs::request::auth::login;
-use rmp_serde::{Serializer,Deserializer,decode::Error};
+use crate::connect;
+use crate::error::Error as E;
+use crate::msf::db;
+use crate::retry::RetryPolicy;
+use crate::tls::TlsConfig;
+use crate::transport::{HttpTransport,Settings,Transport};
+use crate::structs::request::auth::login;
+use crate::structs::request::core::version;
+use crate::value::Value;
+use reqwest::{Proxy,Url};
+use reqwest::header::{HeaderName,HeaderValue};
+use std::convert::TryFrom;
+use std::fmt;
+use std::sync::{Arc,Mutex,RwLock};
+use std::time::Duration;
+use zeroize::Zeroizing;
+use rmp_serde::Serializer;
 use serde::{Serialize,Deserialize};
+use serde::de::DeserializeOwned as DOwned;
 #[derive(Deserialize)]
 struct Reslogin {
     result:String,
     token:String,
 }
+#[derive(Deserialize)]
+struct Resversion {
+    #[allow(dead_code)]
+    version:String,
+}
+/// A logged in connection to the msfrpcd server.
+///
+/// The client keeps its HTTP connection pool alive,so clone it and pass the clones around instead of logging in again.
+/// Clones share the same pool.
 #[derive(Debug,Clone)]
 pub struct Client {
     pub url:String,
+    /// The token the client was created with.[`Client::gettoken`] returns the one in use after a renewal.
     pub token:Option<String>,
+    pub(crate) transport:Arc<dyn Transport>,
+    pub(crate) auth:Option<Arc<Auth>>,
+}
+
+/// The credentials of a client built with [`ClientBuilder::reauthenticate`] and the token it currently uses.
+///
+/// Shared by all clones of the client,so a token renewed by one clone is picked up by the others.
+/// The credentials are wiped from memory when the last clone is dropped.
+pub(crate) struct Auth {
+    user:Zeroizing<String>,
+    password:Zeroizing<String>,
+    token:RwLock<String>,
+    renew:Mutex<()>,
+    #[cfg(feature="async")]
+    renew_async:tokio::sync::Mutex<()>,
+}
+
+impl fmt::Debug for Auth {
+    fn fmt(&self,f:&mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Auth").field("user",&*self.user).field("password",&"<redacted>").finish()
+    }
+}
+
+impl Auth {
+    fn new(user:&str,password:&str,token:String) -> Self {
+        Auth {
+            user:Zeroizing::new(user.to_string()),
+            password:Zeroizing::new(password.to_string()),
+            token:RwLock::new(token),
+            renew:Mutex::new(()),
+            #[cfg(feature="async")]
+            renew_async:tokio::sync::Mutex::new(()),
+        }
+    }
+    fn token(&self) -> String {
+        self.token.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+    fn set_token(&self,token:String) {
+        *self.token.write().unwrap_or_else(|e| e.into_inner())=token;
+    }
+    /// Logs in again after the server rejected the token in `body` and returns `body` with the new token.
+    ///
+    /// When another call already renewed the token,the current one is used without logging in again.
+    /// Returns `None` when the login fails,so the caller keeps the server's original reply.
+    pub(crate) fn renew(&self,client:&Client,body:&[u8]) -> Option<Vec<u8>> {
+        let stale=connect::token_of(body)?;
+        let _guard=self.renew.lock().unwrap_or_else(|e| e.into_inner());
+        if self.token()==stale {
+            let buf=client.transport.send(Client::login_body(&self.user,&self.password)).ok()?;
+            self.set_token(Client::login_token(buf).ok()?);
+        }
+        connect::with_token(body,&self.token())
+    }
+    #[cfg(feature="async")]
+    pub(crate) async fn renew_async(&self,client:&Client,body:&[u8]) -> Option<Vec<u8>> {
+        let stale=connect::token_of(body)?;
+        let _guard=self.renew_async.lock().await;
+        if self.token()==stale {
+            let buf=client.transport.send_async(Client::login_body(&self.user,&self.password)).await.ok()?;
+            self.set_token(Client::login_token(buf).ok()?);
+        }
+        connect::with_token(body,&self.token())
+    }
 }
 impl Client {
     pub fn new(host:&str,port:i32,user:&str,password:&str,ssl:bool) -> Self {
-        let new_user=String::from(user);
-        let url:String;
-        let new_pass=String::from(password);
-        if ssl {
-            url=format!("https://{}:{}/api",host,port).to_string()
-        } else {
-            url=format!("http://{}:{}/api",host,port).to_string()
-        };
+        match Client::try_new(host,port,user,password,ssl) {
+            Ok(client) => client,
+            Err(E::ConnectionError(_)) => panic!("Couldn't connect to the metasploit RPC Server at {}:{}",host,port),
+            Err(e @ E::ConfigError{..}) => panic!("{}",e),
+            Err(_) => panic!("Not authorised.Username or password is wrong"),
+        }
+    }
+    /// Logs in like [`Client::new`] but returns an [`Error`](crate::error::Error) instead of panicking.
+    ///
+    /// A transport failure is returned as `ConnectionError`,a rejected login as `AuthError` with the server's message
+    /// and a reply which is not a login response as `DError`.
+    ///
+    /// With `ssl` the server certificate is verified against the system's root certificates.
+    /// Use [`Client::try_new_with_tls`] to trust your own CA,pin a certificate or accept a self-signed one.
+    pub fn try_new(host:&str,port:i32,user:&str,password:&str,ssl:bool) -> Result<Self,E> {
+        Client::builder().host(host).port(Client::port(port)?).ssl(ssl).login(user,password)
+    }
+    /// Logs in over https using the given TLS settings.
+    pub fn try_new_with_tls(host:&str,port:i32,user:&str,password:&str,tls:TlsConfig) -> Result<Self,E> {
+        Client::builder().host(host).port(Client::port(port)?).tls(tls).login(user,password)
+    }
+    /// Same as [`Client::new`] but logs in through the async transport,so it can be awaited inside a tokio runtime.
+    #[cfg(feature="async")]
+    pub async fn new_async(host:&str,port:i32,user:&str,password:&str,ssl:bool) -> Self {
+        match Client::try_new_async(host,port,user,password,ssl).await {
+            Ok(client) => client,
+            Err(E::ConnectionError(_)) => panic!("Couldn't connect to the metasploit RPC Server at {}:{}",host,port),
+            Err(e @ E::ConfigError{..}) => panic!("{}",e),
+            Err(_) => panic!("Not authorised.Username or password is wrong"),
+        }
+    }
+    /// Async version of [`Client::try_new`].
+    #[cfg(feature="async")]
+    pub async fn try_new_async(host:&str,port:i32,user:&str,password:&str,ssl:bool) -> Result<Self,E> {
+        Client::builder().host(host).port(Client::port(port)?).ssl(ssl).login_async(user,password).await
+    }
+    /// Async version of [`Client::try_new_with_tls`].
+    #[cfg(feature="async")]
+    pub async fn try_new_with_tls_async(host:&str,port:i32,user:&str,password:&str,tls:TlsConfig) -> Result<Self,E> {
+        Client::builder().host(host).port(Client::port(port)?).tls(tls).login_async(user,password).await
+    }
+    /// Starts a [`ClientBuilder`] for timeouts,a custom URI,extra headers and the other connection settings.
+    pub fn builder() -> ClientBuilder {
+        ClientBuilder::default()
+    }
+    /// A client for the RPC endpoint at `url` which uses an existing token (e.g. a permanent one from
+    /// `auth.token_add`) instead of logging in.The token is not checked,see [`ClientBuilder::token_checked`].
+    pub fn from_token(url:&str,token:&str) -> Result<Self,E> {
+        Client::builder().url(url).token(token)
+    }
+    /// Calls any RPC method,including ones this crate doesn't wrap yet.
+    ///
+    /// The token is inserted before `params`,so this is for every method except `auth.login`.An error reply from
+    /// msfrpcd is returned as `MsfError`,like with the wrapped methods.
+    ///
+    /// ## Example
+    /// ```no_run
+    /// use metasploit::client::Client;
+    /// use metasploit::value::Value;
+    /// # fn main() -> Result<(),metasploit::error::Error> {
+    /// let client=Client::try_new("127.0.0.1",55552,"msf","password",true)?;
+    /// let found:Value=client.call("module.search",vec![Value::from("cve:2021-44228")])?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn call<T:DOwned>(&self,method:&str,params:Vec<Value>) -> Result<T,E> {
+        connect::send(self,self.call_body(method,params))
+    }
+    /// Async version of [`Client::call`].
+    #[cfg(feature="async")]
+    pub async fn call_async<T:DOwned>(&self,method:&str,params:Vec<Value>) -> Result<T,E> {
+        connect::send_async(self,self.call_body(method,params)).await
+    }
+    fn call_body(&self,method:&str,params:Vec<Value>) -> Vec<u8> {
+        let mut call=Vec::with_capacity(params.len()+2);
+        call.push(Value::from(method));
+        call.push(Value::from(self.gettoken()));
+        call.extend(params);
         let mut body=Vec::new();
+        rmpv::encode::write_value(&mut body,&Value::Array(call)).unwrap();
+        body
+    }
+    fn port(port:i32) -> Result<u16,E> {
+        u16::try_from(port).map_err(|e| E::ConfigError{message:format!("invalid port {}",port),source:Some(Box::new(e))})
+    }
+    fn check_token(self) -> Result<Self,E> {
         let mut buf=vec![];
+        connect::connect(&self,self.version_body(),&mut buf)?;
+        Client::token_result(connect::decode::<Resversion>("core.version",buf))?;
+        Ok(self)
+    }
+    #[cfg(feature="async")]
+    async fn check_token_async(self) -> Result<Self,E> {
+        let mut buf=vec![];
+        connect::connect_async(&self,self.version_body(),&mut buf).await?;
+        Client::token_result(connect::decode::<Resversion>("core.version",buf))?;
+        Ok(self)
+    }
+    fn version_body(&self) -> Vec<u8> {
+        let mut body=Vec::new();
         let mut serializer=Serializer::new(&mut body);
-        let byte=login("auth.login".to_string(),new_user.clone(),new_pass);
+        let byte=version("core.version".to_string(),self.gettoken());
         byte.serialize(&mut serializer).unwrap();
-        let con=connect::connect(url.clone(),body,&mut buf);
-        let mut de=Deserializer::new(buf.as_slice());
-        let mut token=String::new();
-        match con {
-			Ok(_) => {
-				let de_ret:Result<Reslogin,Error>=Deserialize::deserialize(&mut de);
-				if let Ok(ref val) = de_ret {
-					if val.result=="success".to_string() {
-						token=val.token.clone();
-					} else {
-						panic!("Not authorised.Username or password is wrong");
-					}
-				}
-				if let Err(_) = de_ret {
-					panic!("Not authorised.Username or password is wrong");
-				}
-			},
-			Err(_) => {
-				panic!("Couldn't connect to the metasploit RPC Server at {}:{}",host,port);
-			},
-		}
-		Client {
-			url:url.clone(),
-			token:Some(token.clone()),
-		}
+        body
     }
+    fn token_result(ret:Result<Resversion,E>) -> Result<(),E> {
+        match ret {
+            Ok(_) => Ok(()),
+            Err(E::MsfError(e)) => Err(E::AuthError{message:e.error_message.clone(),source:Some(Box::new(e))}),
+            Err(e) => Err(e),
+        }
+    }
+    fn login(mut self,user:&str,password:&str) -> Result<Self,E> {
+        let mut buf=vec![];
+        connect::connect(&self,Client::login_body(user,password),&mut buf)?;
+        self.token=Some(Client::login_token(buf)?);
+        Ok(self)
+    }
+    #[cfg(feature="async")]
+    async fn login_async(mut self,user:&str,password:&str) -> Result<Self,E> {
+        let mut buf=vec![];
+        connect::connect_async(&self,Client::login_body(user,password),&mut buf).await?;
+        self.token=Some(Client::login_token(buf)?);
+        Ok(self)
+    }
+    fn remember(mut self,user:&str,password:&str) -> Self {
+        self.auth=Some(Arc::new(Auth::new(user,password,self.gettoken())));
+        self
+    }
+    fn login_body(user:&str,password:&str) -> Vec<u8> {
+        let mut body=Vec::new();
+        let mut serializer=Serializer::new(&mut body);
+        let byte=login("auth.login".to_string(),user.to_string(),password.to_string());
+        byte.serialize(&mut serializer).unwrap();
+        body
+    }
+    fn login_token(buf:Vec<u8>) -> Result<String,E> {
+        match connect::decode::<Reslogin>("auth.login",buf) {
+            Ok(val) if val.result=="success" => Ok(val.token),
+            Ok(val) => Err(E::AuthError{message:format!("login returned {}",val.result),source:None}),
+            Err(E::MsfError(e)) => Err(E::AuthError{message:e.error_message.clone(),source:Some(Box::new(e))}),
+            Err(e) => Err(e),
+        }
+    }
+    /// A handle scoping database calls to the workspace `name`,which is created unless it exists.
+    ///
+    /// ```no_run
+    /// use metasploit::client::Client;
+    /// use metasploit::query::HostQuery;
+    /// # #[cfg(not(feature="async"))]
+    /// # fn main() -> Result<(),metasploit::error::Error> {
+    /// let client=Client::builder().login("msf","password")?;
+    /// let acme=client.workspace("acme-ext")?;
+    /// let _:metasploit::value::Value=acme.report_host("10.0.0.5")?;
+    /// for host in acme.hosts_typed(HostQuery::new().only_up(true))? {
+    ///     println!("{} {}",host.address,host.os_name);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// # #[cfg(feature="async")]
+    /// # fn main() {}
+    /// ```
+    #[cfg(not(feature="async"))]
+    pub fn workspace(&self,name:&str) -> Result<db::Workspace,E> {
+        db::Workspace::open(self.clone(),name)
+    }
+    /// A handle scoping database calls to the workspace `name`,which is created unless it exists.
+    #[cfg(feature="async")]
+    pub async fn workspace(&self,name:&str) -> Result<db::Workspace,E> {
+        db::Workspace::open(self.clone(),name).await
+    }
+    /// The token sent with each call.With [`ClientBuilder::reauthenticate`] this is the latest renewed token.
     pub fn gettoken(&self) -> String {
-        self.token.as_ref().unwrap().to_string().clone()
+        match &self.auth {
+            Some(auth) => auth.token(),
+            None => self.token.as_ref().unwrap().to_string(),
+        }
     }
     pub fn geturl(&self) -> String {
     	self.url.clone()
     }
 }
+
+/// The wire protocol used to talk to Metasploit.
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Default)]
+pub enum Protocol {
+    /// msfrpcd's MessagePack RPC,served on `/api`.
+    #[default]
+    MsgPack,
+    /// The JSON-RPC 2.0 web service (`msfdb` webservice or `msf-json-rpc.ru`),served on `/api/v1/json-rpc`.
+    /// The token is sent as a bearer token,so use an API token with [`ClientBuilder::token`].
+    JsonRpc,
+}
+
+/// Configures a [`Client`] before logging in.
+///
+/// The server is given either as host,port and path (`https://127.0.0.1:55553/api` by default,which is what msfrpcd
+/// listens on unless started with `-a`,`-p`,`-u` or `-S`) or as a full URL with [`ClientBuilder::url`].
+/// IPv6 hosts are bracketed automatically.
+///
+/// ## Example
+/// ```no_run
+/// use metasploit::client::Client;
+/// use std::time::Duration;
+/// # fn main() -> Result<(),metasploit::error::Error> {
+/// let client=Client::builder()
+///     .host("::1")
+///     .port(55553)
+///     .path("/msf/api")
+///     .connect_timeout(Duration::from_secs(5))
+///     .timeout(Duration::from_secs(120))
+///     .user_agent("orchestrator/1.0")
+///     .header("X-Engagement","acme-ext")
+///     .login("msf","password")?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug,Clone)]
+pub struct ClientBuilder {
+    url:Option<String>,
+    host:String,
+    port:u16,
+    path:String,
+    ssl:bool,
+    headers:Vec<(String,String)>,
+    proxy:Option<String>,
+    proxy_auth:Option<(String,String)>,
+    reauthenticate:bool,
+    settings:Settings,
+    transport:Option<Arc<dyn Transport>>,
+}
+
+impl Default for ClientBuilder {
+    fn default() -> Self {
+        ClientBuilder {
+            url:None,
+            host:"127.0.0.1".to_string(),
+            port:55553,
+            path:"/api".to_string(),
+            ssl:true,
+            headers:Vec::new(),
+            proxy:None,
+            proxy_auth:None,
+            reauthenticate:false,
+            settings:Settings::default(),
+            transport:None,
+        }
+    }
+}
+
+impl ClientBuilder {
+    /// The full URL of the RPC endpoint,e.g. `https://[::1]:55553/api/`.Overrides host,port,path and ssl.
+    pub fn url(mut self,url:&str) -> Self {
+        self.url=Some(url.to_string());
+        self
+    }
+    pub fn host(mut self,host:&str) -> Self {
+        self.host=host.to_string();
+        self
+    }
+    pub fn port(mut self,port:u16) -> Self {
+        self.port=port;
+        self
+    }
+    /// The URI the server is mounted on (msfrpcd's `-u` option).
+    pub fn path(mut self,path:&str) -> Self {
+        self.path=path.to_string();
+        self
+    }
+    /// Whether to connect over https.Servers started from msfconsole with `load msgrpc` use plain http.
+    pub fn ssl(mut self,ssl:bool) -> Self {
+        self.ssl=ssl;
+        self
+    }
+    /// Which protocol to speak.Also sets the path to the protocol's default,so call [`ClientBuilder::path`]
+    /// afterwards for a server mounted elsewhere.
+    ///
+    /// ```no_run
+    /// use metasploit::client::{Client,Protocol};
+    /// # fn main() -> Result<(),metasploit::error::Error> {
+    /// let client=Client::builder().port(5443).protocol(Protocol::JsonRpc).token_checked("api-token")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn protocol(mut self,protocol:Protocol) -> Self {
+        self.path=match protocol {
+            Protocol::MsgPack => "/api",
+            Protocol::JsonRpc => "/api/v1/json-rpc",
+        }.to_string();
+        self.settings.protocol=protocol;
+        self
+    }
+    /// TLS settings for https connections.Implies `ssl(true)`.
+    pub fn tls(mut self,tls:TlsConfig) -> Self {
+        self.ssl=true;
+        self.settings.tls=tls;
+        self
+    }
+    /// How long to wait for the TCP (and TLS) connection to be established.
+    pub fn connect_timeout(mut self,timeout:Duration) -> Self {
+        self.settings.connect_timeout=Some(timeout);
+        self
+    }
+    /// How long a whole call may take,from sending the request to reading the last byte of the response.
+    pub fn timeout(mut self,timeout:Duration) -> Self {
+        self.settings.timeout=Some(timeout);
+        self
+    }
+    pub fn user_agent(mut self,agent:&str) -> Self {
+        self.settings.user_agent=Some(agent.to_string());
+        self
+    }
+    /// Adds a header sent with every request.Invalid names or values are reported when logging in.
+    pub fn header(mut self,name:&str,value:&str) -> Self {
+        self.headers.push((name.to_string(),value.to_string()));
+        self
+    }
+    /// Sends the login and every call through a proxy,e.g. `http://proxy:3128` or `socks5://127.0.0.1:1080` for an
+    /// SSH dynamic forward (`ssh -D 1080 jumphost`).
+    ///
+    /// With `socks5://` the host name of the server is resolved locally,use `socks5h://` to let the proxy resolve it.
+    /// Credentials can be part of the URL or set with [`ClientBuilder::proxy_auth`].An invalid URL is reported when
+    /// logging in.
+    pub fn proxy(mut self,url:&str) -> Self {
+        self.proxy=Some(url.to_string());
+        self
+    }
+    /// Username and password for the proxy (HTTP basic auth,or the SOCKS5 username/password method).
+    pub fn proxy_auth(mut self,user:&str,password:&str) -> Self {
+        self.proxy_auth=Some((user.to_string(),password.to_string()));
+        self
+    }
+    /// Sends calls which failed with a transient transport error again,see [`RetryPolicy`].
+    /// Without a policy every failure is returned straight away.
+    pub fn retry(mut self,policy:RetryPolicy) -> Self {
+        self.settings.retry=Some(policy);
+        self
+    }
+    /// Logs in again when the server rejects the token,e.g. because a temporary token expired after five minutes
+    /// of inactivity or msfrpcd was restarted.
+    ///
+    /// The client keeps the username and password in memory (wiped when the last clone is dropped).When a call fails
+    /// with `401 Invalid Authentication Token` it logs in once,switches every clone to the new token and sends the
+    /// call again.A failed login returns the original error.Only applies to clients created with
+    /// [`ClientBuilder::login`],a client built from a token has no credentials to log in with.
+    pub fn reauthenticate(mut self,enabled:bool) -> Self {
+        self.reauthenticate=enabled;
+        self
+    }
+    /// Logs in with `auth.login` and returns the client.
+    pub fn login(self,user:&str,password:&str) -> Result<Client,E> {
+        let reauthenticate=self.reauthenticate;
+        let client=self.build()?.login(user,password)?;
+        Ok(if reauthenticate { client.remember(user,password) } else { client })
+    }
+    /// Async version of [`ClientBuilder::login`].
+    #[cfg(feature="async")]
+    pub async fn login_async(self,user:&str,password:&str) -> Result<Client,E> {
+        let reauthenticate=self.reauthenticate;
+        let client=self.build()?.login_async(user,password).await?;
+        Ok(if reauthenticate { client.remember(user,password) } else { client })
+    }
+    /// Uses an existing token instead of logging in.No request is made,so an invalid token only shows up on the first call.
+    pub fn token(self,token:&str) -> Result<Client,E> {
+        let mut client=self.build()?;
+        client.token=Some(token.to_string());
+        Ok(client)
+    }
+    /// Uses an existing token and checks it with a `core.version` call.
+    /// A token the server rejects is returned as `AuthError`.
+    pub fn token_checked(self,token:&str) -> Result<Client,E> {
+        self.token(token)?.check_token()
+    }
+    /// Async version of [`ClientBuilder::token_checked`].
+    #[cfg(feature="async")]
+    pub async fn token_checked_async(self,token:&str) -> Result<Client,E> {
+        self.token(token)?.check_token_async().await
+    }
+    /// Replaces the HTTP transport,e.g. with a [`MemoryTransport`](crate::transport::MemoryTransport) in tests or
+    /// a wrapper around [`ClientBuilder::http_transport`].The connection settings of this builder are then unused.
+    pub fn transport<T:Transport+'static>(mut self,transport:T) -> Self {
+        self.transport=Some(Arc::new(transport));
+        self
+    }
+    /// The HTTP transport this builder would use,for wrapping in a custom [`Transport`].
+    pub fn http_transport(&self) -> Result<HttpTransport,E> {
+        let url=self.build_url()?;
+        let mut settings=self.settings.clone();
+        for (name,value) in &self.headers {
+            let name=HeaderName::from_bytes(name.as_bytes()).map_err(|e| E::ConfigError{message:format!("invalid header name {}",name),source:Some(Box::new(e))})?;
+            let value=HeaderValue::from_str(value).map_err(|e| E::ConfigError{message:format!("invalid value for header {}",name),source:Some(Box::new(e))})?;
+            settings.headers.append(name,value);
+        }
+        if let Some(url)=&self.proxy {
+            let mut proxy=Proxy::all(url.as_str()).map_err(|e| E::ConfigError{message:format!("invalid proxy {}",url),source:Some(Box::new(e))})?;
+            if let Some((user,password))=&self.proxy_auth {
+                proxy=proxy.basic_auth(user,password);
+            }
+            settings.proxy=Some(proxy);
+        }
+        Ok(HttpTransport::new(url,settings))
+    }
+    /// The HTTP settings are only checked when they are used,i.e. when no custom transport is set.
+    fn build(mut self) -> Result<Client,E> {
+        let (url,transport):(String,Arc<dyn Transport>)=match self.transport.take() {
+            Some(transport) => (self.url_string(),transport),
+            None => {
+                let http=self.http_transport()?;
+                (http.url().to_string(),Arc::new(http))
+            },
+        };
+        Ok(Client {
+            url,
+            token:None,
+            transport,
+            auth:None,
+        })
+    }
+    fn url_string(&self) -> String {
+        match &self.url {
+            Some(url) => url.clone(),
+            None => {
+                let scheme=if self.ssl { "https" } else { "http" };
+                let host=if self.host.contains(':') && !self.host.starts_with('[') {
+                    format!("[{}]",self.host)
+                } else {
+                    self.host.clone()
+                };
+                let slash=if self.path.starts_with('/') { "" } else { "/" };
+                format!("{}://{}:{}{}{}",scheme,host,self.port,slash,self.path)
+            },
+        }
+    }
+    fn build_url(&self) -> Result<String,E> {
+        let url=self.url_string();
+        match Url::parse(&url) {
+            Ok(parsed) if parsed.scheme()=="http" || parsed.scheme()=="https" => Ok(url),
+            Ok(_) => Err(E::ConfigError{message:format!("unsupported scheme in {}",url),source:None}),
+            Err(e) => Err(E::ConfigError{message:format!("invalid url {}",url),source:Some(Box::new(e))}),
+        }
+    }
+}
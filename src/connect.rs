@@ -1,16 +1,129 @@
-use reqwest::{header,blocking::Client};
-#[path="./error.rs"] mod error;
-use error::{ConnectionError as conerr};
-
-pub fn connect(url:String,body:Vec<u8>,buf:&mut Vec<u8>) -> Result<(),conerr> {
-	let mut header=header::HeaderMap::new();
-	header.insert(header::CONTENT_TYPE,header::HeaderValue::from_static("binary/message-pack"));
-    let client=Client::builder()
-		.default_headers(header)
-		.danger_accept_invalid_certs(true)
-		.build()
-		.unwrap();
-	let mut reader=client.post(url).body(body).send()?;
-	reader.copy_to(buf).unwrap();
+use crate::client::Client;
+use crate::error::{DecodeError,MsfError,Error as E};
+use rmpv::Value;
+use serde::Deserialize;
+use serde::de::DeserializeOwned as DOwned;
+use rmp_serde::from_slice;
+
+/// Sends a call through the client's transport,logging in again and resending it once when the token expired
+/// and the client remembers its credentials.
+pub fn connect(client:&Client,body:Vec<u8>,buf:&mut Vec<u8>) -> Result<(),E> {
+    let auth=match &client.auth {
+        Some(auth) => auth,
+        None => {
+            *buf=client.transport.send(body)?;
+            return Ok(());
+        },
+    };
+    *buf=client.transport.send(body.clone())?;
+    if invalid_token(buf) {
+        if let Some(body)=auth.renew(client,&body) {
+            *buf=client.transport.send(body)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(feature="async")]
+pub async fn connect_async(client:&Client,body:Vec<u8>,buf:&mut Vec<u8>) -> Result<(),E> {
+    let auth=match &client.auth {
+        Some(auth) => auth,
+        None => {
+            *buf=client.transport.send_async(body).await?;
+            return Ok(());
+        },
+    };
+    *buf=client.transport.send_async(body.clone()).await?;
+    if invalid_token(buf) {
+        if let Some(body)=auth.renew_async(client,&body).await {
+            *buf=client.transport.send_async(body).await?;
+        }
+    }
     Ok(())
 }
+
+#[derive(Deserialize)]
+struct ErrorCode {
+    error:bool,
+    error_code:Option<i64>,
+}
+
+/// Whether the server rejected the token,which msfrpcd reports with `error_code` 401.
+fn invalid_token(buf:&[u8]) -> bool {
+    is_error(buf) && matches!(from_slice::<ErrorCode>(buf),Ok(ErrorCode{error:true,error_code:Some(401)}))
+}
+
+/// Whether a reply is an msfrpcd error map,which starts with `error:true`.Only the first entry is read,so this is
+/// cheap even for replies of several megabytes.
+fn is_error(buf:&[u8]) -> bool {
+    let mut rd=buf;
+    if !matches!(rmp::decode::read_map_len(&mut rd),Ok(len) if len>0) {
+        return false;
+    }
+    let mut key=[0;5];
+    matches!(rmp::decode::read_str(&mut rd,&mut key),Ok("error")) && matches!(rmp::decode::read_bool(&mut rd),Ok(true))
+}
+
+/// The token of a serialized request,which is always the second element after the method name.
+pub fn token_of(body:&[u8]) -> Option<String> {
+    match rmpv::decode::read_value(&mut &body[..]).ok()? {
+        Value::Array(params) => params.get(1)?.as_str().map(|token| token.to_string()),
+        _ => None,
+    }
+}
+
+/// Replaces the token of a serialized request.
+pub fn with_token(body:&[u8],token:&str) -> Option<Vec<u8>> {
+    let mut params=match rmpv::decode::read_value(&mut &body[..]).ok()? {
+        Value::Array(params) if params.len()>1 => params,
+        _ => return None,
+    };
+    params[1]=Value::from(token);
+    let mut body=Vec::new();
+    rmpv::encode::write_value(&mut body,&Value::Array(params)).ok()?;
+    Some(body)
+}
+
+/// The method name of a serialized request,read without decoding the arguments.
+pub fn method_of(body:&[u8]) -> Option<&str> {
+    let mut rd=body;
+    rmp::decode::read_array_len(&mut rd).ok()?;
+    let len=rmp::decode::read_str_len(&mut rd).ok()? as usize;
+    std::str::from_utf8(rd.get(..len)?).ok()
+}
+
+/// Decodes the reply to `method` into `T`,or into the `MsfError` the server sent instead.
+///
+/// Error maps are recognised by their marker before decoding,so they are reported even when `T` could hold them
+/// (e.g. `Value`).A reply is decoded straight from `buf` and only decoded again when it doesn't fit `T`,to find out
+/// whether it is an error map with its entries in another order.
+pub fn decode<T:DOwned>(method:&str,buf:Vec<u8>) -> Result<T,E> {
+    if is_error(&buf) {
+        if let Ok(error)=from_slice::<MsfError>(&buf) {
+            return Err(E::MsfError(error));
+        }
+    }
+    match from_slice::<T>(&buf) {
+        Ok(val) => Ok(val),
+        Err(error) => match from_slice::<MsfError>(&buf) {
+            Ok(val) => Err(E::MsfError(val)),
+            Err(_) => Err(E::DError(DecodeError::new(method,error,buf))),
+        },
+    }
+}
+
+/// Sends a call and decodes the reply.
+pub fn send<T:DOwned>(client:&Client,body:Vec<u8>) -> Result<T,E> {
+    let method=method_of(&body).unwrap_or_default().to_string();
+    let mut buf=vec![];
+    connect(client,body,&mut buf)?;
+    decode(&method,buf)
+}
+
+#[cfg(feature="async")]
+pub async fn send_async<T:DOwned>(client:&Client,body:Vec<u8>) -> Result<T,E> {
+    let method=method_of(&body).unwrap_or_default().to_string();
+    let mut buf=vec![];
+    connect_async(client,body,&mut buf).await?;
+    decode(&method,buf)
+}
@@ -1,13 +1,47 @@
 #![allow(dead_code)]
 use std::fmt::{Result,Display,Formatter,Debug};
 use reqwest;
-use std::env::var;
 use serde::Deserialize as des;
 use rmp_serde::decode;
+use crate::value::Value;
 pub type ConnectionError=reqwest::Error;
 
 pub type DError=decode::Error;
 
+/// A reply which could not be decoded into the type asked for,nor into an [`MsfError`].
+#[derive(Debug)]
+pub struct DecodeError {
+    /// The RPC method which was called,e.g. `module.info`.
+    pub method:String,
+    /// Why the reply didn't fit the type asked for.
+    pub error:DError,
+    /// The reply.One which isn't valid msgpack (e.g. a proxy's HTML error page) is kept as `Value::Binary`.
+    pub response:Value,
+}
+
+impl DecodeError {
+    pub(crate) fn new(method:&str,error:DError,buf:Vec<u8>) -> Self {
+        let mut rd=buf.as_slice();
+        let response=match rmpv::decode::read_value(&mut rd) {
+            Ok(value) if rd.is_empty() => value,
+            _ => Value::Binary(buf),
+        };
+        DecodeError{method:method.to_string(),error,response}
+    }
+}
+
+impl std::error::Error for DecodeError {
+    fn source(&self) -> Option<&(dyn std::error::Error+'static)> {
+        Some(&self.error)
+    }
+}
+
+impl Display for DecodeError {
+    fn fmt(&self,f:&mut Formatter) -> Result {
+        write!(f,"invalid reply to {}: {}",self.method,self.error)
+    }
+}
+
 #[derive(des,Debug)]
 pub struct MsfError {
     pub error:bool,
@@ -15,45 +49,134 @@ pub struct MsfError {
     pub error_string:String,
     pub error_message:String,
     pub error_backtrace:Vec<String>,
+    /// The status msfrpcd attached to the error,e.g. 401 for an invalid token.Older servers don't send it.
+    #[serde(default)]
+    pub error_code:Option<i64>,
+}
+
+/// What went wrong on the server,as told by an [`MsfError`]'s code,class and message.
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Hash)]
+pub enum MsfErrorKind {
+    /// The token is unknown or expired (code 401).
+    InvalidToken,
+    /// The server has no such RPC method (code 404).
+    UnknownMethod,
+    /// The module type or name doesn't exist.
+    ModuleNotFound,
+    /// The session doesn't exist or is of the wrong type for the call.
+    InvalidSession,
+    /// The job doesn't exist.
+    JobNotFound,
+    /// Any other exception raised while handling the call (code 500).
+    ServerError,
+    /// Any other error,or one from a server which sends no code.
+    Other,
+}
+
+impl MsfError {
+    pub fn kind(&self) -> MsfErrorKind {
+        let message=self.error_message.to_ascii_lowercase();
+        match self.error_code {
+            Some(401) => return MsfErrorKind::InvalidToken,
+            // -32601 is JSON-RPC's "method not found".
+            Some(404) | Some(-32601) => return MsfErrorKind::UnknownMethod,
+            _ if message.starts_with("unknown api call") => return MsfErrorKind::UnknownMethod,
+            _ => (),
+        }
+        // msfrpcd raises every other failure as an Msf::RPC::Exception with code 500,told apart only by its message.
+        let raised=self.error_class=="Msf::RPC::Exception" && matches!(self.error_code,Some(500) | None);
+        if raised {
+            if message.starts_with("invalid module") {
+                return MsfErrorKind::ModuleNotFound;
+            }
+            if message.starts_with("unknown session id") || message.starts_with("session ") {
+                return MsfErrorKind::InvalidSession;
+            }
+            if message.starts_with("invalid job") {
+                return MsfErrorKind::JobNotFound;
+            }
+        }
+        match self.error_code {
+            Some(500) => MsfErrorKind::ServerError,
+            _ => MsfErrorKind::Other,
+        }
+    }
 }
 
 impl std::error::Error for MsfError {}
 
+/// The error message.The alternate form (`{:#}`) adds the class and code.
 impl Display for MsfError {
-	fn fmt(&self,f: &mut Formatter) -> Result {
-        let err:String;
-        match var("RUST_BACKTRACE") {
-            Ok(val) => {
-                if val=="1".to_string() {
-                    err=format!("({},{})",self.error_message,self.error_class).to_string()
-                } else if val=="full".to_string() {
-                    err=format!("{:?}",self).to_string();
-                } else {
-                    err=format!("{}",self.error_message).to_string();
-                }
-            },
-            Err(_) => {
-                err=format!("{}",self.error_message);
-            },
+    fn fmt(&self,f:&mut Formatter) -> Result {
+        if !f.alternate() {
+            return write!(f,"{}",self.error_message);
+        }
+        match self.error_code {
+            Some(code) => write!(f,"{} ({},{})",self.error_message,self.error_class,code),
+            None => write!(f,"{} ({})",self.error_message,self.error_class),
         }
-		write!(f,"{}",err)
-	}
+    }
 }
 
 #[derive(Debug)]
 pub enum Error {
     ConnectionError(ConnectionError),
-    DError(DError),
+    /// The reply didn't fit the type asked for.
+    DError(DecodeError),
     MsfError(MsfError),
+    /// The server refused the credentials given to `auth.login`,or the token.Holds the server's error message,and
+    /// the [`MsfError`] it came in as the source.
+    AuthError{message:String,source:Option<Box<dyn std::error::Error+Send+Sync>>},
+    /// The TLS settings could not be applied,e.g. an unreadable CA file or a malformed fingerprint.
+    TlsError{message:String,source:Option<Box<dyn std::error::Error+Send+Sync>>},
+    /// The client configuration is invalid,e.g. a malformed URL,port or header.
+    ConfigError{message:String,source:Option<Box<dyn std::error::Error+Send+Sync>>},
+    /// A custom [`Transport`](crate::transport::Transport) could not deliver the call.
+    TransportError(Box<dyn std::error::Error+Send+Sync>),
+    /// A payload could not be generated as asked,e.g. an unsupported format,or not be written to its file.
+    PayloadError{message:String,source:Option<Box<dyn std::error::Error+Send+Sync>>},
+}
+
+impl Error {
+    /// The kind of error msfrpcd reported,or `None` if the error didn't come from the server.
+    pub fn msf_kind(&self) -> Option<MsfErrorKind> {
+        match self {
+            Error::MsfError(e) => Some(e.kind()),
+            _ => None,
+        }
+    }
+    /// The code msfrpcd sent with the error,if any.
+    pub fn error_code(&self) -> Option<i64> {
+        match self {
+            Error::MsfError(e) => e.error_code,
+            _ => None,
+        }
+    }
 }
 
-impl std::error::Error for Error {}
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error+'static)> {
+        match self {
+            Error::ConnectionError(e) => Some(e),
+            Error::DError(e) => Some(e),
+            Error::MsfError(e) => Some(e),
+            Error::TransportError(e) => Some(e.as_ref()),
+            Error::AuthError{source,..} | Error::TlsError{source,..} | Error::ConfigError{source,..} |
+            Error::PayloadError{source,..} => source.as_deref().map(|e| e as &(dyn std::error::Error+'static)),
+        }
+    }
+}
 impl Display for Error {
     fn fmt(&self,f:&mut Formatter) -> Result {
         match self {
             Error::ConnectionError(e) => Display::fmt(&e,f),
             Error::DError(e) =>  Display::fmt(&e,f),
             Error::MsfError(e) =>  Display::fmt(&e,f),
+            Error::AuthError{message,..} => write!(f,"Authentication failed: {}",message),
+            Error::TlsError{message,..} => write!(f,"TLS error: {}",message),
+            Error::ConfigError{message,..} => write!(f,"Invalid client configuration: {}",message),
+            Error::TransportError(e) => write!(f,"Transport error: {}",e),
+            Error::PayloadError{message,..} => write!(f,"Payload generation failed: {}",message),
         }
     }
 }
@@ -63,8 +186,8 @@ impl From<ConnectionError> for Error {
         Error::ConnectionError(e)
     }
 }
-impl From<DError> for Error {
-    fn from(e:DError) -> Error {
+impl From<DecodeError> for Error {
+    fn from(e:DecodeError) -> Error {
         Error::DError(e)
     }
 }
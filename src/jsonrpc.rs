@@ -0,0 +1,219 @@
+//! Translation between the msgpack calls built by this crate and Metasploit's JSON-RPC 2.0 web service.
+//!
+//! Calls are still serialized as msgpack arrays (`[method,token,params...]`) and replies are handed back as msgpack,
+//! so every `msf::*` function works unchanged over either protocol.
+use crate::error::Error as E;
+use crate::value::Value;
+use serde_json::{json,Value as Json};
+use std::sync::atomic::{AtomicU64,Ordering};
+
+static ID:AtomicU64=AtomicU64::new(1);
+
+/// The JSON-RPC request for a serialized call,and the token to send as bearer token.
+///
+/// `auth.login` is the only call without a token.A body which is not a call can't be translated,so it fails with
+/// `TransportError` instead of being sent as msgpack.
+pub fn request(body:&[u8]) -> Result<(Vec<u8>,Option<String>),E> {
+    translate(body).ok_or_else(|| E::TransportError("request is not an RPC call".into()))
+}
+
+fn translate(body:&[u8]) -> Option<(Vec<u8>,Option<String>)> {
+    let mut call=match rmpv::decode::read_value(&mut &body[..]).ok()? {
+        Value::Array(call) if !call.is_empty() => call.into_iter(),
+        _ => return None,
+    };
+    let method=call.next()?.as_str()?.to_string();
+    let token=if method=="auth.login" {
+        None
+    } else {
+        call.next().and_then(|token| token.as_str().map(|token| token.to_string()))
+    };
+    let params=serde_json::to_value(call.collect::<Vec<Value>>()).ok()?;
+    let request=json!({"jsonrpc":"2.0","method":method,"params":params,"id":ID.fetch_add(1,Ordering::Relaxed)});
+    Some((serde_json::to_vec(&request).ok()?,token))
+}
+
+/// The msgpack form of a JSON-RPC reply: its `result`,or an msfrpcd style error map for its `error` object.
+///
+/// A reply which is not JSON is passed through,so it is reported as a decode error.
+pub fn response(reply:&[u8]) -> Vec<u8> {
+    let mut reply:Json=match serde_json::from_slice(reply) {
+        Ok(reply) => reply,
+        Err(_) => return reply.to_vec(),
+    };
+    let value=match reply.get_mut("result") {
+        Some(result) => result.take(),
+        None => match reply.get("error") {
+            Some(error) if error.is_object() => msf_error(error),
+            _ => reply,
+        },
+    };
+    rmp_serde::to_vec_named(&value).unwrap_or_default()
+}
+
+/// The error map msfrpcd sends over msgpack,filled from a JSON-RPC error object.
+/// Metasploit puts the RPC exception's details (code,class,backtrace) in the error's `data`.
+fn msf_error(error:&Json) -> Json {
+    let code=error.get("code").cloned().unwrap_or(Json::Null);
+    let message=error.get("message").and_then(Json::as_str).unwrap_or_default();
+    let data=|name:&str| error.get("data").and_then(|data| data.get(name));
+    let class=match data("error_class").and_then(Json::as_str) {
+        Some(class) => class.to_string(),
+        None => format!("JSON-RPC error {}",code),
+    };
+    json!({
+        "error":true,
+        "error_class":class,
+        "error_string":message,
+        "error_message":data("error_message").and_then(Json::as_str).unwrap_or(message),
+        "error_backtrace":data("error_backtrace").filter(|trace| trace.is_array()).cloned().unwrap_or_else(|| json!([])),
+        "error_code":data("code").cloned().unwrap_or(code),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::{Client,Protocol};
+    use crate::connect;
+    use crate::error::{MsfError,MsfErrorKind};
+    use crate::structs::response::IdMap;
+    use crate::structs::response::core::ThreadInfo;
+    use crate::structs::response::modules::{ModuleInfo,ModuleTarget};
+    use crate::structs::response::sessions::{SessionInfo,SessionType};
+    use std::io::{BufRead,BufReader,Read,Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    fn call(call:Value) -> Vec<u8> {
+        let mut body=Vec::new();
+        rmpv::encode::write_value(&mut body,&call).unwrap();
+        body
+    }
+
+    fn json(body:&[u8]) -> Json {
+        serde_json::from_slice(body).unwrap()
+    }
+
+    /// Answers one HTTP request with `reply` and returns the request's headers (lower-cased) and body.
+    fn serve(reply:&'static str) -> (String,thread::JoinHandle<(Vec<String>,Json)>) {
+        let listener=TcpListener::bind("127.0.0.1:0").unwrap();
+        let url=format!("http://{}/api/v1/json-rpc",listener.local_addr().unwrap());
+        let server=thread::spawn(move || {
+            let mut stream=BufReader::new(listener.accept().unwrap().0);
+            let mut headers=Vec::new();
+            loop {
+                let mut line=String::new();
+                stream.read_line(&mut line).unwrap();
+                if line.trim().is_empty() {
+                    break;
+                }
+                headers.push(line.trim().to_ascii_lowercase());
+            }
+            let length=headers.iter().find_map(|header| header.strip_prefix("content-length: ")).unwrap().parse().unwrap();
+            let mut body=vec![0;length];
+            stream.read_exact(&mut body).unwrap();
+            let response=format!("HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{}",reply.len(),reply);
+            stream.get_mut().write_all(response.as_bytes()).unwrap();
+            (headers,json(&body))
+        });
+        (url,server)
+    }
+
+    #[test]
+    fn request_takes_the_token_out_of_the_params() {
+        let (body,token)=request(&call(Value::Array(vec!["module.info".into(),"TEST".into(),"exploit".into(),"multi/handler".into()]))).unwrap();
+        assert_eq!(token.as_deref(),Some("TEST"));
+        let body=json(&body);
+        assert_eq!((&body["jsonrpc"],&body["method"]),(&json!("2.0"),&json!("module.info")));
+        assert_eq!(body["params"],json!(["exploit","multi/handler"]));
+        assert!(body["id"].is_u64());
+
+        let (body,token)=request(&call(Value::Array(vec!["auth.login".into(),"msf".into(),"password".into()]))).unwrap();
+        assert_eq!(token,None);
+        assert_eq!(json(&body)["params"],json!(["msf","password"]));
+    }
+
+    #[test]
+    fn request_rejects_what_is_not_a_call() {
+        for body in [call(Value::Array(vec![])),call(Value::from("core.version")),call(Value::Array(vec![Value::from(1)])),vec![0xc1]] {
+            assert!(matches!(request(&body),Err(E::TransportError(_))),"{:?}",body);
+        }
+    }
+
+    #[test]
+    fn response_result_is_msgpack() {
+        let reply=response(br#"{"jsonrpc":"2.0","result":{"version":"6.3.44","ruby":"3.0.2","api":"1.0"},"id":1}"#);
+        let value=rmpv::decode::read_value(&mut reply.as_slice()).unwrap();
+        assert_eq!(value["version"],Value::from("6.3.44"));
+        assert_eq!(value["api"],Value::from("1.0"));
+        assert_eq!(response(b"<html>502 Bad Gateway</html>"),b"<html>502 Bad Gateway</html>");
+    }
+
+    #[test]
+    fn response_error_is_msf_error() {
+        let reply=response(br#"{"jsonrpc":"2.0","error":{"code":-32601,"message":"Method not found"},"id":1}"#);
+        let error:MsfError=rmp_serde::from_slice(&reply).unwrap();
+        assert_eq!(error.error_code,Some(-32601));
+        assert_eq!(error.kind(),MsfErrorKind::UnknownMethod);
+        assert_eq!((error.error_class.as_str(),error.error_message.as_str()),("JSON-RPC error -32601","Method not found"));
+
+        let reply=response(br#"{"jsonrpc":"2.0","error":{"code":-32000,"message":"Application server error","data":{"code":401,"error_class":"Msf::RPC::Exception","error_message":"Invalid Authentication Token"}},"id":2}"#);
+        let error:MsfError=rmp_serde::from_slice(&reply).unwrap();
+        assert_eq!((error.error_code,error.kind()),(Some(401),MsfErrorKind::InvalidToken));
+        assert_eq!(error.error_class,"Msf::RPC::Exception");
+
+        let reply=response(br#"{"jsonrpc":"2.0","error":{"code":-32000,"message":"Application server error","data":{"code":500,"error_class":"Msf::RPC::Exception","error_message":"Unknown Session ID 9"}},"id":3}"#);
+        let error:MsfError=rmp_serde::from_slice(&reply).unwrap();
+        assert_eq!((error.error_code,error.kind()),(Some(500),MsfErrorKind::InvalidSession));
+    }
+
+    #[test]
+    fn thread_ids_are_string_keys() {
+        let reply=response(br#"{"jsonrpc":"2.0","result":{"0":{"status":"sleep","critical":false,"name":"StreamServerListener","started":"2023-07-22 04:26:40 +0000"}},"id":1}"#);
+        let threads:IdMap<ThreadInfo>=connect::decode("core.thread_list",reply).unwrap();
+        assert_eq!(threads.0[&0].name,"StreamServerListener");
+        let reply=response(br#"{"jsonrpc":"2.0","result":{"main":{}},"id":1}"#);
+        assert!(matches!(connect::decode::<IdMap<ThreadInfo>>("core.thread_list",reply),Err(E::DError(_))));
+    }
+
+    #[test]
+    fn session_ids_are_string_keys() {
+        let reply=response(br#"{"jsonrpc":"2.0","result":{"1":{"type":"meterpreter","tunnel_peer":"10.0.0.5:49152","session_port":445},"2":{"type":"shell"}},"id":1}"#);
+        let sessions:IdMap<SessionInfo>=connect::decode("session.list",reply).unwrap();
+        assert_eq!(sessions.0.keys().collect::<Vec<_>>(),[&1,&2]);
+        assert_eq!((&sessions.0[&1].session_type,sessions.0[&1].session_port),(&SessionType::Meterpreter,445));
+        assert_eq!(sessions.0[&2].session_type,SessionType::Shell);
+    }
+
+    #[test]
+    fn module_targets_are_string_keys() {
+        let reply=response(br#"{"jsonrpc":"2.0","result":{"type":"exploit","name":"MS17-010 EternalBlue","targets":{"0":"Automatic Target","1":"Windows 7"},"default_target":0,"actions":{"0":"Scan"}},"id":1}"#);
+        let info:ModuleInfo=connect::decode("module.info",reply).unwrap();
+        assert_eq!(info.targets,[
+            ModuleTarget{index:0,name:"Automatic Target".to_string()},
+            ModuleTarget{index:1,name:"Windows 7".to_string()},
+        ]);
+        assert_eq!((info.default_target,info.actions[&0].as_str()),(Some(0),"Scan"));
+    }
+
+    #[test]
+    fn client_sends_bearer_token() {
+        let (url,server)=serve(r#"{"jsonrpc":"2.0","result":{"version":"6.3.44","ruby":"3.0.2","api":"1.0"},"id":1}"#);
+        let client=Client::builder().url(&url).protocol(Protocol::JsonRpc).token("API-TOKEN").unwrap();
+        let version:Value=client.call("core.version",vec![]).unwrap();
+        assert_eq!(version["version"],Value::from("6.3.44"));
+        let (headers,body)=server.join().unwrap();
+        assert!(headers.contains(&"authorization: bearer api-token".to_string()),"{:?}",headers);
+        assert_eq!((&body["method"],&body["params"]),(&json!("core.version"),&json!([])));
+    }
+
+    #[test]
+    fn client_reports_json_rpc_errors() {
+        let (url,server)=serve(r#"{"jsonrpc":"2.0","error":{"code":-32601,"message":"Method not found"},"id":1}"#);
+        let client=Client::builder().url(&url).protocol(Protocol::JsonRpc).token("API-TOKEN").unwrap();
+        let error=client.call::<Value>("core.versions",vec![]).unwrap_err();
+        assert_eq!((error.msf_kind(),error.error_code()),(Some(MsfErrorKind::UnknownMethod),Some(-32601)));
+        server.join().unwrap();
+    }
+}
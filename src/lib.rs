@@ -6,15 +6,23 @@
 //! Async features needs to be used when you are dealing with tokio crate too.
 //! For installation , add the follwing line in your Cargo.toml under dependencies
 //!
-//! ```
+//! ```toml
 //! [dependencies]
 //! rust-metasploit={ version="1.2.0",features=["blocking"] }
 //! ```
+//!
+//! With the async feature every function in [`msf`] is an `async fn` that goes through reqwest's async client,
+//! so awaiting a call yields to the runtime instead of blocking the executor thread.
+//!
+//! ```toml
+//! [dependencies]
+//! rust-metasploit={ version="1.2.0",default-features=false,features=["async"] }
+//! ```
 //! # Rust-Metasploit
 //! As said earlier,this library is usedto connect with the msfrpcd server.
 //!
 //! ## Example
-//! ```
+//! ```no_run
 //! use metasploit::client::Client;
 //! fn main() {
 //!     let client=Client::new("127.0.0.1",55552,"msf","password",true);
@@ -26,28 +34,43 @@
 //! There is also another example such as this one below
 //! 
 //! ## Example
-//! ```
+//! ```no_run
 //! use metasploit::client::Client;
 //! use metasploit::msf::auth;
-//! use serde::Deserializeas des;
+//! use serde::Deserialize as des;
 //!
-//! [#derive(des,Debug)]
+//! #[derive(des,Debug)]
 //! struct AddToken {
 //!     pub result:String,
 //! }
+//! # #[cfg(not(feature="async"))]
 //! fn main() {
 //!     let client=Client::new("127.0.0.1",55552,"msf","password",true);
-//!     let token:AddToken=auth::add_token(client,"newtoken");
+//!     let token:AddToken=auth::add_token(client,"newtoken").unwrap();
 //!     println!("{}",token.result);
 //! }
+//! # #[cfg(feature="async")]
+//! # fn main() {}
 //! ```
 //!
 //! Here the data type of token variable can be a custom created struct which can phrase the response from the msfrpcd server.
 //! The data type struct should only have feilds that are defined by the server.Any other exception will lead to an error.
-//! Some of the data types are defined in the previous versions of rust-metaslpoit (They are not up-to-date with the latest release).
+//! Maintained types for the common replies are in [`response`],and are returned by the `*_typed` functions such as
+//! [`msf::core::version_typed`].
 //! For more information on the data types , you can refer the metasploit documentation.
 //!
 pub mod client;
 pub mod msf;
 pub mod error;
 pub mod value;
+pub mod tls;
+pub mod retry;
+pub mod transport;
+pub mod cassette;
+#[cfg(feature="mock")]
+pub mod mock;
+mod connect;
+mod jsonrpc;
+mod structs;
+pub use structs::query;
+pub use structs::response;
@@ -0,0 +1,867 @@
+//! A local stand-in for msfrpcd,so code using this crate can be tested without a Metasploit install.
+//!
+//! Enabled with the `mock` feature.[`MockServer::start`] listens on a free localhost port and speaks the msgpack RPC
+//! protocol over plain http.It keeps state for tokens,consoles,jobs,sessions,plugins,global settings,workspaces,
+//! hosts and vulnerabilities,and answers every other method with a plausible canned reply.Any reply can be replaced with
+//! [`MockServer::respond`] or [`MockServer::respond_error`].
+//!
+//! Like msfrpcd,every call except `auth.login` needs a valid token,and errors are sent as msgpack error maps
+//! with the error code as HTTP status.
+//!
+//! ## Example
+//! ```
+//! use metasploit::error::Error;
+//! use metasploit::mock::MockServer;
+//! use metasploit::value::Value;
+//! # fn main() -> Result<(),metasploit::error::Error> {
+//! let server=MockServer::start();
+//! let id=server.add_session("meterpreter");
+//! server.respond_error("core.version",500,"Internal Server Error");
+//! let client=server.client();
+//! let sessions:Value=client.call("session.list",vec![])?;
+//! assert_eq!(sessions.as_map().unwrap()[0].0,Value::from(id));
+//! let version=client.call::<Value>("core.version",vec![]);
+//! assert!(matches!(version,Err(Error::MsfError(_))));
+//! # Ok(())
+//! # }
+//! ```
+use crate::client::Client;
+use crate::value::Value;
+use std::collections::{BTreeMap,HashMap};
+use std::io::{BufRead,BufReader,Read,Write};
+use std::net::{TcpListener,TcpStream};
+use std::sync::atomic::{AtomicBool,Ordering};
+use std::sync::{Arc,Mutex,MutexGuard};
+use std::thread;
+
+const PROMPT:&str="msf6 > ";
+const EXPLOITS:&[&str]=&["windows/smb/ms17_010_eternalblue","multi/handler","unix/ftp/vsftpd_234_backdoor"];
+const AUXILIARY:&[&str]=&["scanner/portscan/tcp","scanner/smb/smb_version"];
+const POST:&[&str]=&["multi/recon/local_exploit_suggester","windows/gather/hashdump"];
+const PAYLOADS:&[&str]=&["windows/x64/meterpreter/reverse_tcp","linux/x64/shell_reverse_tcp","cmd/unix/reverse"];
+const ENCODERS:&[&str]=&["x86/shikata_ga_nai","generic/none"];
+const NOPS:&[&str]=&["x86/single_byte"];
+const EXECUTABLE_FORMATS:&[&str]=&["asp","aspx","dll","elf","elf-so","exe","exe-service","hta-psh","jar","macho","msi","psh","vba","war"];
+const TRANSFORM_FORMATS:&[&str]=&["base64","bash","c","csharp","hex","java","perl","powershell","python","raw","ruby","rust"];
+const COMMANDS:&[&str]=&["back","exit","help","info","jobs","search","sessions","set","setg","show","use","version"];
+const SESSION_CALLS:&[&str]=&[
+    "stop","shell_read","shell_write","shell_upgrade","ring_put","ring_clear","ring_last","compatible_modules",
+    "meterpreter_read","meterpreter_write","meterpreter_run_single","meterpreter_tabs","meterpreter_script",
+    "meterpreter_session_detach","meterpreter_session_kill",
+];
+const TIME:i64=1690000000;
+
+/// A running mock msfrpcd.The server stops accepting connections when this is dropped.
+#[derive(Debug)]
+pub struct MockServer {
+    url:String,
+    user:String,
+    password:String,
+    state:Arc<Mutex<State>>,
+    stop:Arc<AtomicBool>,
+}
+
+#[derive(Debug,Default)]
+struct State {
+    user:String,
+    password:String,
+    tokens:Vec<String>,
+    permanent:Vec<String>,
+    issued:u64,
+    canned:HashMap<String,(u16,Value)>,
+    calls:Vec<Vec<Value>>,
+    consoles:BTreeMap<u32,String>,
+    next_console:u32,
+    jobs:BTreeMap<u32,(String,HashMap<String,String>)>,
+    next_job:u32,
+    sessions:BTreeMap<u32,Session>,
+    next_session:u32,
+    plugins:Vec<String>,
+    globals:HashMap<String,String>,
+    workspaces:Vec<String>,
+    workspace:String,
+    hosts:Vec<(String,String)>,
+    /// The workspace,address and name of each reported vulnerability.
+    vulns:Vec<(String,String,String)>,
+}
+
+#[derive(Debug,Default)]
+struct Session {
+    kind:String,
+    output:String,
+    ring:Vec<String>,
+}
+
+impl MockServer {
+    /// Starts a server accepting the username `msf` and the password `password`.
+    pub fn start() -> Self {
+        MockServer::with_credentials("msf","password")
+    }
+    /// Starts a server accepting the given credentials for `auth.login`.
+    pub fn with_credentials(user:&str,password:&str) -> Self {
+        let listener=TcpListener::bind("127.0.0.1:0").expect("couldn't bind the mock server");
+        let url=format!("http://{}/api",listener.local_addr().unwrap());
+        let state=Arc::new(Mutex::new(State {
+            user:user.to_string(),
+            password:password.to_string(),
+            workspaces:vec!["default".to_string()],
+            workspace:"default".to_string(),
+            ..State::default()
+        }));
+        let stop=Arc::new(AtomicBool::new(false));
+        let (shared,stopped)=(state.clone(),stop.clone());
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                if stopped.load(Ordering::SeqCst) {
+                    break;
+                }
+                if let Ok(stream)=stream {
+                    let state=shared.clone();
+                    thread::spawn(move || serve(stream,state));
+                }
+            }
+        });
+        MockServer {
+            url,
+            user:user.to_string(),
+            password:password.to_string(),
+            state,
+            stop,
+        }
+    }
+    /// The RPC endpoint,e.g. `http://127.0.0.1:40123/api`.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+    /// A client logged in to this server.
+    ///
+    /// This uses the blocking login,so inside a tokio runtime build the client with
+    /// `Client::builder().url(server.url()).login_async(..)` instead.
+    pub fn client(&self) -> Client {
+        Client::builder().url(&self.url).login(&self.user,&self.password).expect("couldn't log in to the mock server")
+    }
+    /// Answers every following call of `method` with `reply` instead of the built-in behaviour.
+    pub fn respond(&self,method:&str,reply:Value) {
+        self.lock().canned.insert(method.to_string(),(200,reply));
+    }
+    /// Answers every following call of `method` with an msfrpcd error,e.g. `(500,"Invalid Module")`.
+    pub fn respond_error(&self,method:&str,code:u16,message:&str) {
+        self.lock().canned.insert(method.to_string(),error(code,message));
+    }
+    /// The calls received so far,each as `[method,token,params...]`.
+    pub fn calls(&self) -> Vec<Vec<Value>> {
+        self.lock().calls.clone()
+    }
+    /// Forgets every temporary token,as msfrpcd does when they time out.Permanent tokens stay valid.
+    pub fn expire_tokens(&self) {
+        self.lock().tokens.clear();
+    }
+    /// Opens a fake session of the given type (`shell` or `meterpreter`) and returns its id.
+    pub fn add_session(&self,kind:&str) -> u32 {
+        let mut state=self.lock();
+        state.next_session+=1;
+        let id=state.next_session;
+        state.sessions.insert(id,Session{kind:kind.to_string(),..Session::default()});
+        id
+    }
+    /// Queues output for the next read of a console or session.
+    pub fn push_output(&self,id:u32,output:&str,console:bool) {
+        let mut state=self.lock();
+        let buffer=if console {
+            state.consoles.get_mut(&id)
+        } else {
+            state.sessions.get_mut(&id).map(|session| &mut session.output)
+        };
+        if let Some(buffer)=buffer {
+            buffer.push_str(output);
+        }
+    }
+    fn lock(&self) -> MutexGuard<'_,State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.stop.store(true,Ordering::SeqCst);
+        let _=TcpStream::connect(self.url.trim_start_matches("http://").trim_end_matches("/api"));
+    }
+}
+
+fn serve(stream:TcpStream,state:Arc<Mutex<State>>) {
+    let mut writer=match stream.try_clone() {
+        Ok(writer) => writer,
+        Err(_) => return,
+    };
+    let _=stream.set_nodelay(true);
+    let mut reader=BufReader::new(stream);
+    loop {
+        let mut length=0;
+        loop {
+            let mut line=String::new();
+            if reader.read_line(&mut line).unwrap_or(0)==0 {
+                return;
+            }
+            let line=line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            if let Some((name,value))=line.split_once(':') {
+                if name.eq_ignore_ascii_case("content-length") {
+                    length=value.trim().parse().unwrap_or(0);
+                }
+            }
+        }
+        let mut body=vec![0;length];
+        if reader.read_exact(&mut body).is_err() {
+            return;
+        }
+        let (status,reply)=match rmpv::decode::read_value(&mut body.as_slice()) {
+            Ok(Value::Array(call)) if !call.is_empty() => state.lock().unwrap_or_else(|e| e.into_inner()).handle(call),
+            _ => error(500,"Invalid Message Format"),
+        };
+        let mut reply_body=Vec::new();
+        rmpv::encode::write_value(&mut reply_body,&reply).unwrap();
+        let mut response=format!("HTTP/1.1 {} {}\r\nContent-Type: binary/message-pack\r\nContent-Length: {}\r\n\r\n",status,if status==200 { "OK" } else { "Error" },reply_body.len()).into_bytes();
+        response.extend_from_slice(&reply_body);
+        if writer.write_all(&response).is_err() {
+            return;
+        }
+    }
+}
+
+fn map(fields:Vec<(&str,Value)>) -> Value {
+    Value::Map(fields.into_iter().map(|(name,value)| (Value::from(name),value)).collect())
+}
+
+fn ok(fields:Vec<(&str,Value)>) -> (u16,Value) {
+    (200,map(fields))
+}
+
+fn success() -> (u16,Value) {
+    ok(vec![("result",Value::from("success"))])
+}
+
+fn failure() -> (u16,Value) {
+    ok(vec![("result",Value::from("failure"))])
+}
+
+fn error(code:u16,message:&str) -> (u16,Value) {
+    (code,map(vec![
+        ("error",Value::from(true)),
+        ("error_class",Value::from("Msf::RPC::Exception")),
+        ("error_string",Value::from(message)),
+        ("error_message",Value::from(message)),
+        ("error_backtrace",Value::Array(vec![Value::from("lib/msf/core/rpc/v10/service.rb:150:in `process'")])),
+        ("error_code",Value::from(code)),
+    ]))
+}
+
+fn strings(items:&[&str]) -> Value {
+    Value::Array(items.iter().map(|item| Value::from(*item)).collect())
+}
+
+fn modules(kind:&str) -> &'static [&'static str] {
+    match kind {
+        "exploit" => EXPLOITS,
+        "auxiliary" => AUXILIARY,
+        "post" => POST,
+        "payload" => PAYLOADS,
+        "encoder" => ENCODERS,
+        "nop" => NOPS,
+        _ => &[],
+    }
+}
+
+fn module_stats() -> (u16,Value) {
+    let count=|items:&[&str]| Value::from(items.len());
+    ok(vec![
+        ("exploits",count(EXPLOITS)),
+        ("auxiliary",count(AUXILIARY)),
+        ("post",count(POST)),
+        ("encoders",count(ENCODERS)),
+        ("nops",count(NOPS)),
+        ("payloads",count(PAYLOADS)),
+    ])
+}
+
+fn host(workspace:&str,address:&str) -> Value {
+    map(vec![
+        ("created_at",Value::from(TIME)),
+        ("address",Value::from(address)),
+        ("mac",Value::from("")),
+        ("name",Value::from("")),
+        ("state",Value::from("alive")),
+        ("os_name",Value::from("Windows")),
+        ("os_flavor",Value::from("")),
+        ("os_sp",Value::from("")),
+        ("os_lang",Value::from("")),
+        ("updated_at",Value::from(TIME)),
+        ("purpose",Value::from("server")),
+        ("info",Value::from("")),
+        ("workspace",Value::from(workspace)),
+    ])
+}
+
+fn service() -> Value {
+    map(vec![
+        ("host",Value::from("10.0.0.5")),
+        ("created_at",Value::from(TIME)),
+        ("updated_at",Value::from(TIME)),
+        ("port",Value::from(445)),
+        ("proto",Value::from("tcp")),
+        ("state",Value::from("open")),
+        ("name",Value::from("smb")),
+        ("info",Value::from("Windows Server 2016")),
+    ])
+}
+
+fn vuln(address:&str,name:&str) -> Value {
+    map(vec![
+        ("port",Value::from(445)),
+        ("proto",Value::from("tcp")),
+        ("time",Value::from(TIME)),
+        ("host",Value::from(address)),
+        ("name",Value::from(name)),
+        ("refs",Value::from("CVE-2017-0144,MSB-MS17-010")),
+    ])
+}
+
+fn note() -> Value {
+    map(vec![
+        ("time",Value::from(TIME)),
+        ("host",Value::from("10.0.0.5")),
+        ("service",Value::from("smb")),
+        ("type",Value::from("smb.fingerprint")),
+        ("data",Value::from("{:native_os=>\"Windows Server 2016\"}")),
+    ])
+}
+
+fn web_client() -> Value {
+    map(vec![
+        ("host",Value::from("10.0.0.7")),
+        ("ua_string",Value::from("Mozilla/5.0 (Windows NT 10.0; Win64; x64)")),
+        ("ua_name",Value::from("Firefox")),
+        ("ua_ver",Value::from("115.0")),
+        ("created_at",Value::from(TIME)),
+        ("updated_at",Value::from(TIME)),
+    ])
+}
+
+impl State {
+    fn handle(&mut self,call:Vec<Value>) -> (u16,Value) {
+        self.calls.push(call.clone());
+        let method=call[0].as_str().unwrap_or_default().to_string();
+        let args=&call[1..];
+        if method=="auth.login" {
+            if let Some(reply)=self.canned.get(&method) {
+                return reply.clone();
+            }
+            return self.login(args);
+        }
+        let token=str_arg(args,0);
+        if !self.tokens.contains(&token) && !self.permanent.contains(&token) {
+            return error(401,"Invalid Authentication Token");
+        }
+        if let Some(reply)=self.canned.get(&method) {
+            return reply.clone();
+        }
+        let args=&args[1..];
+        match method.split_once('.') {
+            Some(("auth",name)) => self.auth(name,args),
+            Some(("console",name)) => self.console(name,args),
+            Some(("core",name)) => self.core(name,args),
+            Some(("job",name)) => self.job(name,args),
+            Some(("module",name)) => self.module(name,args),
+            Some(("plugin",name)) => self.plugin(name,args),
+            Some(("session",name)) => self.session(name,args),
+            Some(("db",name)) => self.db(name,args),
+            _ => error(404,&format!("Unknown API Call: '{}'",method)),
+        }
+    }
+    fn issue(&mut self,prefix:&str) -> String {
+        self.issued+=1;
+        format!("{}{:0width$x}",prefix,self.issued,width=32-prefix.len())
+    }
+    fn login(&mut self,args:&[Value]) -> (u16,Value) {
+        if str_arg(args,0)!=self.user || str_arg(args,1)!=self.password {
+            return error(401,"Login Failed");
+        }
+        let token=self.issue("TEMP");
+        self.tokens.push(token.clone());
+        ok(vec![("result",Value::from("success")),("token",Value::from(token))])
+    }
+    fn auth(&mut self,name:&str,args:&[Value]) -> (u16,Value) {
+        let token=str_arg(args,0);
+        match name {
+            "logout" => match self.tokens.iter().position(|t| *t==token) {
+                Some(index) => {
+                    self.tokens.remove(index);
+                    success()
+                },
+                None => error(500,"Invalid Authentication Token"),
+            },
+            "token_add" => {
+                self.permanent.push(token);
+                success()
+            },
+            "token_generate" => {
+                let token=self.issue("");
+                self.permanent.push(token.clone());
+                ok(vec![("result",Value::from("success")),("token",Value::from(token))])
+            },
+            "token_list" => {
+                let tokens:Vec<&str>=self.tokens.iter().chain(self.permanent.iter()).map(|t| t.as_str()).collect();
+                ok(vec![("tokens",strings(&tokens))])
+            },
+            "token_remove" => {
+                self.permanent.retain(|t| *t!=token);
+                success()
+            },
+            _ => unknown("auth",name),
+        }
+    }
+    fn console(&mut self,name:&str,args:&[Value]) -> (u16,Value) {
+        let describe=|id:u32| map(vec![("id",Value::from(id.to_string())),("prompt",Value::from(PROMPT)),("busy",Value::from(false))]);
+        if name=="create" {
+            let id=self.next_console;
+            self.next_console+=1;
+            self.consoles.insert(id,String::new());
+            return (200,describe(id));
+        }
+        if name=="list" {
+            return ok(vec![("consoles",Value::Array(self.consoles.keys().map(|id| describe(*id)).collect()))]);
+        }
+        let id=match id_arg(args,0) {
+            Some(id) if self.consoles.contains_key(&id) => id,
+            _ => return failure(),
+        };
+        match name {
+            "destroy" => {
+                self.consoles.remove(&id);
+                success()
+            },
+            "write" => {
+                let data=str_arg(args,1);
+                let output=self.consoles.get_mut(&id).unwrap();
+                output.push_str(PROMPT);
+                output.push_str(&data);
+                ok(vec![("wrote",Value::from(data.len()))])
+            },
+            "read" => {
+                let data=std::mem::take(self.consoles.get_mut(&id).unwrap());
+                ok(vec![("data",Value::from(data)),("prompt",Value::from(PROMPT)),("busy",Value::from(false))])
+            },
+            "session_detach" | "session_kill" => success(),
+            "tabs" => ok(vec![("tabs",tabs(&str_arg(args,1)))]),
+            _ => unknown("console",name),
+        }
+    }
+    fn core(&mut self,name:&str,args:&[Value]) -> (u16,Value) {
+        match name {
+            "add_module_path" | "module_stats" | "reload_modules" => module_stats(),
+            "save" | "stop" | "thread_kill" => success(),
+            "setg" => {
+                self.globals.insert(str_arg(args,0),str_arg(args,1));
+                success()
+            },
+            "unsetg" => {
+                self.globals.remove(&str_arg(args,0));
+                success()
+            },
+            "thread_list" => (200,Value::Map(vec![(Value::from(0),map(vec![
+                ("status",Value::from("sleep")),
+                ("critical",Value::from(false)),
+                ("name",Value::from("StreamServerListener")),
+                ("started",Value::from("2023-07-22 04:26:40 +0000")),
+            ]))])),
+            "version" => ok(vec![
+                ("version",Value::from("6.3.25-dev")),
+                ("ruby",Value::from("3.0.5 x86_64-linux 2022-11-24")),
+                ("api",Value::from("1.0")),
+            ]),
+            _ => unknown("core",name),
+        }
+    }
+    fn job(&mut self,name:&str,args:&[Value]) -> (u16,Value) {
+        if name=="list" {
+            return (200,Value::Map(self.jobs.iter().map(|(id,(name,_))| (Value::from(id.to_string()),Value::from(name.as_str()))).collect()));
+        }
+        let id=match id_arg(args,0) {
+            Some(id) if self.jobs.contains_key(&id) => id,
+            _ if name!="info" && name!="stop" => return unknown("job",name),
+            _ => return error(500,"Invalid Job"),
+        };
+        match name {
+            "info" => {
+                let (name,datastore)=&self.jobs[&id];
+                ok(vec![
+                    ("jid",Value::from(id)),
+                    ("name",Value::from(name.as_str())),
+                    ("start_time",Value::from(TIME)),
+                    ("datastore",Value::Map(datastore.iter().map(|(k,v)| (Value::from(k.as_str()),Value::from(v.as_str()))).collect())),
+                ])
+            },
+            "stop" => {
+                self.jobs.remove(&id);
+                success()
+            },
+            _ => unknown("job",name),
+        }
+    }
+    fn module(&mut self,name:&str,args:&[Value]) -> (u16,Value) {
+        let list=|kind:&str| ok(vec![("modules",strings(modules(kind)))]);
+        match name {
+            "exploits" => list("exploit"),
+            "auxiliary" => list("auxiliary"),
+            "post" => list("post"),
+            "payloads" => list("payload"),
+            "encoders" => list("encoder"),
+            "nops" => list("nop"),
+            "info" | "options" | "execute" => {
+                let (kind,module)=(str_arg(args,0),str_arg(args,1));
+                if !modules(&kind).contains(&module.as_str()) {
+                    return error(500,"Invalid Module");
+                }
+                match name {
+                    "info" => (200,module_info(&kind,&module)),
+                    "options" => (200,module_options()),
+                    _ => self.execute(&kind,&module,map_arg(args,2)),
+                }
+            },
+            "executable_formats" => (200,strings(EXECUTABLE_FORMATS)),
+            "transform_formats" => (200,strings(TRANSFORM_FORMATS)),
+            "compatible_payloads" | "target_compatible_payloads" => ok(vec![("payloads",strings(PAYLOADS))]),
+            "compatible_sessions" => ok(vec![("sessions",Value::Array(self.sessions.keys().map(|id| Value::from(*id)).collect()))]),
+            "encode" => {
+                let encoded:String=str_arg(args,0).bytes().map(|byte| format!("\\x{:02x}",byte)).collect();
+                ok(vec![("encoded",Value::from(encoded))])
+            },
+            _ => unknown("module",name),
+        }
+    }
+    fn execute(&mut self,kind:&str,module:&str,options:HashMap<String,String>) -> (u16,Value) {
+        if kind=="payload" {
+            if let Some(format)=options.get("Format").filter(|format| !EXECUTABLE_FORMATS.contains(&format.as_str()) && !TRANSFORM_FORMATS.contains(&format.as_str())) {
+                return error(500,&format!("failed to generate: Invalid format: {}",format));
+            }
+            return ok(vec![("payload",Value::Binary(b"\xfc\x48\x83\xe4\xf0\xe8\xcc\x00\x00\x00".to_vec()))]);
+        }
+        let id=self.next_job;
+        self.next_job+=1;
+        let title=format!("{}{}",kind[..1].to_uppercase(),&kind[1..]);
+        self.jobs.insert(id,(format!("{}: {}",title,module),options));
+        ok(vec![("job_id",Value::from(id)),("uuid",Value::from(format!("{:024x}",id+1)))])
+    }
+    fn plugin(&mut self,name:&str,args:&[Value]) -> (u16,Value) {
+        let plugin=str_arg(args,0);
+        match name {
+            "load" => {
+                if !self.plugins.contains(&plugin) {
+                    self.plugins.push(plugin);
+                }
+                success()
+            },
+            "unload" => match self.plugins.iter().position(|p| *p==plugin) {
+                Some(index) => {
+                    self.plugins.remove(index);
+                    success()
+                },
+                None => failure(),
+            },
+            "loaded" => {
+                let plugins:Vec<&str>=self.plugins.iter().map(|p| p.as_str()).collect();
+                ok(vec![("plugins",strings(&plugins))])
+            },
+            _ => unknown("plugin",name),
+        }
+    }
+    fn session(&mut self,name:&str,args:&[Value]) -> (u16,Value) {
+        if name=="list" {
+            return (200,Value::Map(self.sessions.iter().map(|(id,session)| (Value::from(*id),session_info(*id,&session.kind))).collect()));
+        }
+        let id=match id_arg(args,0) {
+            Some(id) if self.sessions.contains_key(&id) => id,
+            _ if !SESSION_CALLS.contains(&name) => return unknown("session",name),
+            _ => return error(500,"Unknown Session ID"),
+        };
+        let session=self.sessions.get_mut(&id).unwrap();
+        let expected=match name {
+            "shell_read" | "shell_write" | "shell_upgrade" => Some("shell"),
+            _ if name.starts_with("meterpreter_") => Some("meterpreter"),
+            _ => None,
+        };
+        if let Some(kind)=expected {
+            if session.kind!=kind {
+                return error(500,&format!("Session {} is not a {} session",id,kind));
+            }
+        }
+        let data=str_arg(args,1);
+        match name {
+            "stop" => {
+                self.sessions.remove(&id);
+                success()
+            },
+            "shell_read" => ok(vec![("seq",Value::from(session.ring.len())),("data",Value::from(std::mem::take(&mut session.output)))]),
+            "meterpreter_read" => ok(vec![("data",Value::from(std::mem::take(&mut session.output)))]),
+            "shell_write" | "ring_put" => {
+                session.output.push_str(&data);
+                session.ring.push(data.clone());
+                ok(vec![("write_count",Value::from(data.len()))])
+            },
+            "meterpreter_write" | "meterpreter_run_single" => {
+                session.output.push_str(&data);
+                session.ring.push(data);
+                success()
+            },
+            "ring_clear" => {
+                session.ring.clear();
+                success()
+            },
+            "ring_last" => ok(vec![("seq",Value::from(session.ring.len()))]),
+            "meterpreter_tabs" => ok(vec![("tabs",tabs(&data))]),
+            "compatible_modules" => ok(vec![("modules",strings(&["multi/recon/local_exploit_suggester","multi/manage/shell_to_meterpreter"]))]),
+            "meterpreter_script" | "meterpreter_session_detach" | "meterpreter_session_kill" | "shell_upgrade" => success(),
+            _ => unknown("session",name),
+        }
+    }
+    fn db(&mut self,name:&str,args:&[Value]) -> (u16,Value) {
+        let options=map_arg(args,0);
+        let workspace=options.get("workspace").cloned().unwrap_or_else(|| self.workspace.clone());
+        let address=options.get("host").cloned().unwrap_or_default();
+        let list=|name:&str,item:Value| ok(vec![(name,Value::Array(vec![item]))]);
+        match name {
+            "status" => ok(vec![("driver",Value::from("postgresql")),("db",Value::from("msf"))]),
+            "driver" => match options.get("driver") {
+                Some(_) => success(),
+                None => ok(vec![("driver",Value::from("postgresql"))]),
+            },
+            "connect" | "disconnect" | "import_data" | "report_service" | "report_note" |
+            "report_client" | "report_event" | "report_loot" => success(),
+            "hosts" => {
+                let addresses:Vec<&str>=field(args,"addresses").and_then(Value::as_array).map(|a| a.iter().filter_map(Value::as_str).collect()).unwrap_or_default();
+                let hosts=self.hosts.iter().filter(|(w,a)| *w==workspace && (addresses.is_empty() || addresses.contains(&a.as_str())));
+                ok(vec![("hosts",page(args,hosts.map(|(w,a)| host(w,a))))])
+            },
+            "get_host" => {
+                let hosts=self.hosts.iter().filter(|(w,a)| *w==workspace && *a==address).map(|(w,a)| host(w,a)).collect();
+                ok(vec![("host",Value::Array(hosts))])
+            },
+            "report_host" => {
+                if !self.hosts.contains(&(workspace.clone(),address.clone())) {
+                    self.hosts.push((workspace,address));
+                }
+                success()
+            },
+            "del_host" => {
+                self.hosts.retain(|(w,a)| *w!=workspace || *a!=address);
+                ok(vec![("result",Value::from("success")),("deleted",strings(&[&address]))])
+            },
+            "services" => list("services",service()),
+            "get_service" => list("service",service()),
+            "report_vuln" => {
+                self.vulns.push((workspace,address,options.get("name").cloned().unwrap_or_default()));
+                success()
+            },
+            "vulns" => {
+                let addresses:Vec<&str>=field(args,"addresses").and_then(Value::as_array).map(|a| a.iter().filter_map(Value::as_str).collect()).unwrap_or_default();
+                let vulns=self.vulns.iter().filter(|(w,a,_)| *w==workspace && (addresses.is_empty() || addresses.contains(&a.as_str())));
+                ok(vec![("vulns",page(args,vulns.map(|(_,a,n)| vuln(a,n))))])
+            },
+            "get_vuln" => list("vuln",vuln("10.0.0.5","MS17-010 EternalBlue SMB Remote Windows Kernel Pool Corruption")),
+            "notes" => list("notes",note()),
+            "get_note" => list("note",note()),
+            "clients" => list("clients",web_client()),
+            "get_client" => list("client",web_client()),
+            "del_vuln" => {
+                self.vulns.retain(|(w,a,_)| *w!=workspace || *a!=address);
+                ok(vec![("result",Value::from("success")),("deleted",Value::Array(vec![]))])
+            },
+            "del_service" | "del_note" | "del_client" => {
+                ok(vec![("result",Value::from("success")),("deleted",Value::Array(vec![]))])
+            },
+            "events" => list("events",map(vec![
+                ("host",Value::from("10.0.0.5")),
+                ("created_at",Value::from(TIME)),
+                ("updated_at",Value::from(TIME)),
+                ("name",Value::from("module_run")),
+                ("critical",Value::from(false)),
+                ("username",Value::from("msf")),
+                ("info",Value::from("{:module_name=>\"exploit/windows/smb/ms17_010_eternalblue\"}")),
+            ])),
+            "loots" => list("loots",map(vec![
+                ("host",Value::from("10.0.0.5")),
+                ("service",Value::from("smb")),
+                ("ltype",Value::from("windows.hashes")),
+                ("ctype",Value::from("text/plain")),
+                ("data",Value::from("Administrator:500:aad3b435b51404eeaad3b435b51404ee:31d6cfe0d16ae931b73c59d7e0c089c0:::")),
+                ("created_at",Value::from(TIME)),
+                ("updated_at",Value::from(TIME)),
+                ("name",Value::from("hashes.txt")),
+                ("info",Value::from("Windows Hashes")),
+            ])),
+            "creds" => list("creds",map(vec![
+                ("host",Value::from("10.0.0.5")),
+                ("updated_at",Value::from(TIME)),
+                ("port",Value::from(445)),
+                ("proto",Value::from("tcp")),
+                ("sname",Value::from("smb")),
+                ("type",Value::from("password")),
+                ("user",Value::from("Administrator")),
+                ("pass",Value::from("Passw0rd!")),
+                ("active",Value::from(true)),
+            ])),
+            "get_ref" => ok(vec![("name",Value::from(str_arg(args,0)))]),
+            "workspaces" => {
+                let workspaces=self.workspaces.iter().enumerate().map(|(id,name)| workspace_info(id,name)).collect();
+                ok(vec![("workspaces",Value::Array(workspaces))])
+            },
+            "current_workspace" => {
+                let id=self.workspaces.iter().position(|w| *w==self.workspace).unwrap_or(0);
+                ok(vec![("workspace",Value::from(self.workspace.as_str())),("workspace_id",Value::from(id+1))])
+            },
+            "get_workspace" | "set_workspace" | "del_workspace" | "add_workspace" => {
+                let name_arg=str_arg(args,0);
+                let position=self.workspaces.iter().position(|w| *w==name_arg);
+                match (name,position) {
+                    ("get_workspace",Some(id)) => ok(vec![("workspace",Value::Array(vec![workspace_info(id,&name_arg)]))]),
+                    ("set_workspace",Some(_)) => {
+                        self.workspace=name_arg;
+                        success()
+                    },
+                    ("del_workspace",Some(id)) => {
+                        self.workspaces.remove(id);
+                        self.hosts.retain(|(w,_)| *w!=name_arg);
+                        if self.workspace==name_arg {
+                            self.workspace="default".to_string();
+                        }
+                        success()
+                    },
+                    ("add_workspace",None) => {
+                        self.workspaces.push(name_arg);
+                        success()
+                    },
+                    ("add_workspace",Some(_)) => success(),
+                    _ => error(500,"Invalid workspace"),
+                }
+            },
+            _ => unknown("db",name),
+        }
+    }
+}
+
+fn unknown(group:&str,name:&str) -> (u16,Value) {
+    error(404,&format!("Unknown API Call: '{}.{}'",group,name))
+}
+
+fn str_arg(args:&[Value],index:usize) -> String {
+    match args.get(index) {
+        Some(Value::String(s)) => s.as_str().unwrap_or_default().to_string(),
+        Some(Value::Integer(i)) => i.to_string(),
+        _ => String::new(),
+    }
+}
+
+fn id_arg(args:&[Value],index:usize) -> Option<u32> {
+    str_arg(args,index).parse().ok()
+}
+
+fn map_arg(args:&[Value],index:usize) -> HashMap<String,String> {
+    match args.get(index) {
+        Some(Value::Map(fields)) => fields.iter().filter_map(|(k,v)| Some((k.as_str()?.to_string(),v.as_str()?.to_string()))).collect(),
+        _ => HashMap::new(),
+    }
+}
+
+/// A non-string option of the options map in `args[0]`,e.g. `limit`.
+fn field<'a>(args:&'a [Value],name:&str) -> Option<&'a Value> {
+    args.first()?.as_map()?.iter().find(|(k,_)| k.as_str()==Some(name)).map(|(_,v)| v)
+}
+
+/// The records a listing's `offset` and `limit` select,100 by default like msfrpcd.
+fn page(args:&[Value],records:impl Iterator<Item=Value>) -> Value {
+    let offset=field(args,"offset").and_then(Value::as_u64).unwrap_or(0) as usize;
+    let limit=field(args,"limit").and_then(Value::as_u64).unwrap_or(100) as usize;
+    Value::Array(records.skip(offset).take(limit).collect())
+}
+
+fn tabs(line:&str) -> Value {
+    let matching:Vec<&str>=COMMANDS.iter().copied().filter(|command| command.starts_with(line.trim())).collect();
+    strings(&matching)
+}
+
+fn module_info(kind:&str,module:&str) -> Value {
+    map(vec![
+        ("type",Value::from(kind)),
+        ("name",Value::from(module)),
+        ("fullname",Value::from(format!("{}/{}",kind,module))),
+        ("rank",Value::from("excellent")),
+        ("disclosuredate",Value::from("2017-03-14")),
+        ("description",Value::from("A module served by the mock msfrpcd.")),
+        ("license",Value::from("Metasploit Framework License (BSD)")),
+        ("filepath",Value::from(format!("/usr/share/metasploit-framework/modules/{}s/{}.rb",kind,module))),
+        ("arch",strings(&["x64"])),
+        ("platform",strings(&["Msf::Module::Platform::Windows"])),
+        ("authors",strings(&["msf"])),
+        ("privileged",Value::from(false)),
+        ("check",Value::from(true)),
+        ("references",Value::Array(vec![strings(&["CVE","2017-0144"])])),
+        ("targets",Value::Map(vec![(Value::from(0),Value::from("Automatic Target"))])),
+        ("default_target",Value::from(0)),
+        ("stance",Value::from("aggressive")),
+        ("options",module_options()),
+    ])
+}
+
+fn module_options() -> Value {
+    let option=|kind:&str,required:bool,advanced:bool,desc:&str,default:Option<Value>| {
+        let mut fields=vec![
+            ("type",Value::from(kind)),
+            ("required",Value::from(required)),
+            ("advanced",Value::from(advanced)),
+            ("evasion",Value::from(false)),
+            ("desc",Value::from(desc)),
+        ];
+        if let Some(default)=default {
+            fields.push(("default",default));
+        }
+        map(fields)
+    };
+    map(vec![
+        ("RHOSTS",option("addressrange",true,false,"The target host(s)",None)),
+        ("RPORT",option("port",true,false,"The target port (TCP)",Some(Value::from(445)))),
+        ("VERBOSE",option("bool",false,true,"Enable detailed status messages",Some(Value::from(false)))),
+        ("SMBDomain",option("string",false,false,"The Windows domain to use for authentication",Some(Value::from(".")))),
+    ])
+}
+
+fn session_info(id:u32,kind:&str) -> Value {
+    map(vec![
+        ("type",Value::from(kind)),
+        ("tunnel_local",Value::from("10.0.0.2:4444")),
+        ("tunnel_peer",Value::from(format!("10.0.0.5:{}",49151+id))),
+        ("via_exploit",Value::from("exploit/windows/smb/ms17_010_eternalblue")),
+        ("via_payload",Value::from(if kind=="meterpreter" { "payload/windows/x64/meterpreter/reverse_tcp" } else { "payload/cmd/unix/reverse" })),
+        ("desc",Value::from(if kind=="meterpreter" { "Meterpreter" } else { "Command shell" })),
+        ("info",Value::from(if kind=="meterpreter" { "NT AUTHORITY\\SYSTEM @ WIN-TARGET" } else { "" })),
+        ("workspace",Value::from("default")),
+        ("session_host",Value::from("10.0.0.5")),
+        ("session_port",Value::from(445)),
+        ("target_host",Value::from("10.0.0.5")),
+        ("username",Value::from("msf")),
+        ("uuid",Value::from(format!("{:08x}",id))),
+        ("exploit_uuid",Value::from(format!("{:08x}",id+100))),
+        ("routes",Value::from("")),
+        ("arch",Value::from(if kind=="meterpreter" { "x64" } else { "cmd" })),
+        ("platform",Value::from(if kind=="meterpreter" { "windows" } else { "unix" })),
+    ])
+}
+
+fn workspace_info(id:usize,name:&str) -> Value {
+    map(vec![
+        ("id",Value::from(id+1)),
+        ("name",Value::from(name)),
+        ("created_at",Value::from(TIME)),
+        ("updated_at",Value::from(TIME)),
+    ])
+}
@@ -1,24 +1,48 @@
 use crate::client;
-#[path="../blocking/auth.rs"] mod auth;
+use crate::connect::send_async;
 use crate::error::Error as E;
-use serde::de::DeserializeOwned as DOwned;
+use crate::structs::request as req;
+use serde::{Serialize,de::DeserializeOwned as DOwned};
+use rmp_serde::Serializer;
 
 pub async fn logout<T:DOwned>(clientdata:client::Client) -> Result<T,E> {
-    auth::logout(clientdata.clone())
+    let mut body=Vec::new();
+    let mut serializer=Serializer::new(&mut body);
+    let byte=req::auth::logout("auth.logout".to_string(),clientdata.gettoken(),clientdata.gettoken());
+    byte.serialize(&mut serializer).unwrap();
+    send_async(&clientdata,body).await
 }
 
 pub async fn add_token<T:DOwned>(clientdata:client::Client,newtokenstr:&str) -> Result<T,E> {
-    auth::add_token(clientdata.clone(),newtokenstr)
+    let new_tok:String=newtokenstr.to_string();
+    let mut body=Vec::new();
+    let mut serializer=Serializer::new(&mut body);
+    let byte=req::auth::tokenadd("auth.token_add".to_string(),clientdata.gettoken(),new_tok);
+    byte.serialize(&mut serializer).unwrap();
+    send_async(&clientdata,body).await
 }
 
 pub async fn generate_token<T:DOwned>(clientdata:client::Client) -> Result<T,E> {
-    auth::generate_token(clientdata.clone())
+    let mut body=Vec::new();
+    let mut serializer=Serializer::new(&mut body);
+    let byte=req::auth::tokengen("auth.token_generate".to_string(),clientdata.gettoken());
+    byte.serialize(&mut serializer).unwrap();
+    send_async(&clientdata,body).await
 }
 
 pub async fn list_token<T:DOwned>(clientdata:client::Client) -> Result<T,E> {
-    auth::list_token(clientdata.clone())
+    let mut body=Vec::new();
+    let mut serializer=Serializer::new(&mut body);
+    let byte=req::auth::tokenlist("auth.token_list".to_string(),clientdata.gettoken());
+    byte.serialize(&mut serializer).unwrap();
+    send_async(&clientdata,body).await
 }
 
 pub async fn remove_token<T:DOwned>(clientdata:client::Client,tokenremove:&str) -> Result<T,E> {
-    auth::remove_token(clientdata.clone(),tokenremove)
+    let token_rem:String=tokenremove.to_string();
+    let mut body=Vec::new();
+    let mut serializer=Serializer::new(&mut body);
+    let byte=req::auth::tokenrem("auth.token_remove".to_string(),clientdata.gettoken(),token_rem);
+    byte.serialize(&mut serializer).unwrap();
+    send_async(&clientdata,body).await
 }
@@ -1,30 +1,72 @@
 #![allow(non_snake_case)]
 use crate::error::Error as E;
 use crate::client::Client;
-#[path="../blocking/console.rs"] mod console;
-use serde::de::DeserializeOwned as DOwned;
+use crate::connect::send_async;
+use crate::structs::request as req;
+use serde::{Serialize,de::DeserializeOwned as DOwned};
+use rmp_serde::Serializer;
 
 pub async fn create<T:DOwned>(client:Client) -> Result<T,E> {
-    console::create(client.clone())
+    let mut body=Vec::new();
+    let mut serializer=Serializer::new(&mut body);
+    let byte=req::console::create("console.create".to_string(),client.gettoken());
+    byte.serialize(&mut serializer).unwrap();
+    send_async(&client,body).await
 }
 pub async fn destroy<T:DOwned>(client:Client,consoleID:&str) -> Result<T,E> {
-    console::destroy(client.clone(),consoleID)
+    let consoleid:String=consoleID.to_string();
+    let mut body=Vec::new();
+    let mut serializer=Serializer::new(&mut body);
+    let byte=req::console::destroy("console.destroy".to_string(),client.gettoken(),consoleid);
+    byte.serialize(&mut serializer).unwrap();
+    send_async(&client,body).await
 }
 pub async fn list<T:DOwned>(client:Client) -> Result<T,E> {
-	console::list(client.clone())
+    let mut body=Vec::new();
+    let mut serializer=Serializer::new(&mut body);
+    let byte=req::console::list("console.list".to_string(),client.gettoken());
+    byte.serialize(&mut serializer).unwrap();
+    send_async(&client,body).await
 }
 pub async fn write<T:DOwned>(client:Client,consoleID:&str,command:&str) -> Result<T,E> {
-    console::write(client.clone(),consoleID,command)
+    let consoleid:String=consoleID.to_string();
+    let data:String=command.to_string();
+    let mut body=Vec::new();
+    let mut serializer=Serializer::new(&mut body);
+    let byte=req::console::write("console.write".to_string(),client.gettoken(),consoleid,data);
+    byte.serialize(&mut serializer).unwrap();
+    send_async(&client,body).await
 }
 pub async fn read<T:DOwned>(client:Client,consoleID:&str) -> Result<T,E> {
-    console::read(client.clone(),consoleID)
+    let consoleid:String=consoleID.to_string();
+    let mut body=Vec::new();
+    let mut serializer=Serializer::new(&mut body);
+    let byte=req::console::read("console.read".to_string(),client.gettoken(),consoleid);
+    byte.serialize(&mut serializer).unwrap();
+    send_async(&client,body).await
 }
 pub async fn detach_session<T:DOwned>(client:Client,consoleID:&str) -> Result<T,E> {
-    console::detach_session(client.clone(),consoleID)
+    let consoleid:String=consoleID.to_string();
+    let mut body=Vec::new();
+    let mut serializer=Serializer::new(&mut body);
+    let byte=req::console::session_detach("console.session_detach".to_string(),client.gettoken(),consoleid);
+    byte.serialize(&mut serializer).unwrap();
+    send_async(&client,body).await
 }
 pub async fn kill_session<T:DOwned>(client:Client,consoleID:&str) -> Result<T,E> {
-    console::kill_session(client.clone(),consoleID)
+    let consoleid:String=consoleID.to_string();
+    let mut body=Vec::new();
+    let mut serializer=Serializer::new(&mut body);
+    let byte=req::console::session_kill("console.session_kill".to_string(),client.gettoken(),consoleid);
+    byte.serialize(&mut serializer).unwrap();
+    send_async(&client,body).await
 }
 pub async fn tabs<T:DOwned>(client:Client,consoleID:&str,inputlinestr:&str) -> Result<T,E> {
-    console::tabs(client.clone(),consoleID,inputlinestr)
+    let consoleid:String=consoleID.to_string();
+    let inputline:String=inputlinestr.to_string();
+    let mut body=Vec::new();
+    let mut serializer=Serializer::new(&mut body);
+    let byte=req::console::tabs("console.tabs".to_string(),client.gettoken(),consoleid,inputline);
+    byte.serialize(&mut serializer).unwrap();
+    send_async(&client,body).await
 }
@@ -1,36 +1,97 @@
 #![allow(non_snake_case)]
 use crate::error::Error as E;
 use crate::client::Client;
-#[path="../blocking/core.rs"] mod core;
-use serde::de::DeserializeOwned as DOwned;
+use crate::connect::send_async;
+use crate::structs::request as req;
+use crate::structs::response::IdMap;
+use crate::structs::response::core::{ModuleStats,ThreadInfo,VersionInfo};
+use std::collections::BTreeMap;
+use serde::{Serialize,de::DeserializeOwned as DOwned};
+use rmp_serde::Serializer;
 
 pub async fn add_module<T:DOwned>(client:Client,pathstr:&str) -> Result<T,E> {
-    core::add_module(client.clone(),pathstr)
+    let path:String=pathstr.to_string();
+    let mut body=Vec::new();
+    let mut se=Serializer::new(&mut body);
+    let byte=req::core::addmodpath("core.add_module_path".to_string(),client.gettoken(),path);
+    byte.serialize(&mut se).unwrap();
+    send_async(&client,body).await
 }
 pub async fn module_status<T:DOwned>(client:Client) -> Result<T,E> {
-    core::module_status(client.clone())
+    let mut body=Vec::new();
+    let mut se=Serializer::new(&mut body);
+    let byte=req::core::modulestat("core.module_stats".to_string(),client.gettoken());
+    byte.serialize(&mut se).unwrap();
+    send_async(&client,body).await
 }
 pub async fn reload_module<T:DOwned>(client:Client) -> Result<T,E> {
-    core::reload_module(client.clone())
+    let mut body=Vec::new();
+    let mut se=Serializer::new(&mut body);
+    let byte=req::core::reloadmod("core.reload_modules".to_string(),client.gettoken());
+    byte.serialize(&mut se).unwrap();
+    send_async(&client,body).await
 }
 pub async fn save<T:DOwned>(client:Client) -> Result<T,E> {
-    core::save(client.clone())
+    let mut body=Vec::new();
+    let mut se=Serializer::new(&mut body);
+    let byte=req::core::save("core.save".to_string(),client.gettoken());
+    byte.serialize(&mut se).unwrap();
+    send_async(&client,body).await
 }
 pub async fn setg<T:DOwned>(client:Client,namestr:&str,valuestr:&str) -> Result<T,E> {
-    core::setg(client.clone(),namestr,valuestr)
+    let name:String=namestr.to_string();
+    let value:String=valuestr.to_string();
+    let mut body=Vec::new();
+    let mut se=Serializer::new(&mut body);
+    let byte=req::core::setg("core.setg".to_string(),client.gettoken(),name,value);
+    byte.serialize(&mut se).unwrap();
+    send_async(&client,body).await
 }
 pub async fn unsetg<T:DOwned>(client:Client,namestr:&str) -> Result<T,E> {
-    core::unsetg(client.clone(),namestr)
+    let name:String=namestr.to_string();
+    let mut body=Vec::new();
+    let mut se=Serializer::new(&mut body);
+    let byte=req::core::unsetg("core.unsetg".to_string(),client.gettoken(),name);
+    byte.serialize(&mut se).unwrap();
+    send_async(&client,body).await
 }
 pub async fn list_thread<T:DOwned>(client:Client) -> Result<T,E> {
-    core::list_thread(client.clone())
+    let mut body=Vec::new();
+    let mut se=Serializer::new(&mut body);
+    let byte=req::core::threadlist("core.thread_list".to_string(),client.gettoken());
+    byte.serialize(&mut se).unwrap();
+    send_async(&client,body).await
 }
 pub async fn kill_thread<T:DOwned>(client:Client,threadID:i32) -> Result<T,E> {
-    core::kill_thread(client.clone(),threadID)
+    let mut body=Vec::new();
+    let mut se=Serializer::new(&mut body);
+    let byte=req::core::threadkill("core.thread_kill".to_string(),client.gettoken(),threadID);
+    byte.serialize(&mut se).unwrap();
+    send_async(&client,body).await
 }
 pub async fn version<T:DOwned>(client:Client) -> Result<T,E> {
-    core::version(client.clone())
+    let mut body=Vec::new();
+    let mut se=Serializer::new(&mut body);
+    let byte=req::core::version("core.version".to_string(),client.gettoken());
+    byte.serialize(&mut se).unwrap();
+    send_async(&client,body).await
 }
 pub async fn stop<T:DOwned>(client:Client) -> Result<T,E> {
-    core::stop(client.clone())
+    let mut body=Vec::new();
+    let mut se=Serializer::new(&mut body);
+    let byte=req::core::stop("core.stop".to_string(),client.gettoken());
+    byte.serialize(&mut se).unwrap();
+    send_async(&client,body).await
+}
+/// [`module_status`] decoded into [`ModuleStats`].
+pub async fn module_status_typed(client:Client) -> Result<ModuleStats,E> {
+    module_status(client).await
+}
+/// [`list_thread`] decoded into [`ThreadInfo`]s by thread id.
+pub async fn list_thread_typed(client:Client) -> Result<BTreeMap<u32,ThreadInfo>,E> {
+    list_thread::<IdMap<ThreadInfo>>(client).await.map(|threads| threads.0)
+}
+/// [`version`] decoded into [`VersionInfo`].
+pub async fn version_typed(client:Client) -> Result<VersionInfo,E> {
+    version(client).await
 }
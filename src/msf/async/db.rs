@@ -1,137 +1,570 @@
-use crate::{error::Error as E,client::Client};
-#[path="../blocking/db.rs"] mod db;
-use serde::de::DeserializeOwned as DOwned;
+#![allow(non_camel_case_types)]
+use crate::error::Error as E;
+use crate::structs::request as req;
+use crate::client::Client;
+use crate::value::Value;
+use crate::connect::send_async;
 use std::fs::File;
+use std::io::Read;
 use std::collections::HashMap;
+use serde::{Serialize,de::DeserializeOwned as DOwned};
+use rmp_serde::Serializer;
+use crate::structs::query::{ClientQuery,HostQuery,ListQuery,NoteQuery,ServiceQuery,VulnQuery};
+use crate::structs::response::db::{Cred,Event,Host,Loot,Note,Service,Vuln,WebClient};
+use futures_core::Stream;
+use std::future::{poll_fn,Future};
+use std::pin::Pin;
+use std::task::{Context,Poll};
 
-pub async fn hosts<T:DOwned>(client:Client) -> Result<T,E> {
-    db::hosts(client)
-}
-pub async fn get_host<T:DOwned>(client:Client,workspace:Option<String>,host:&str) -> Result<T,E> {
-    db::get_host(client,workspace,host)
+pub async fn hosts<T:DOwned>(client:Client,query:HostQuery) -> Result<T,E> {
+    let mut body=Vec::new();
+    let mut serializer=Serializer::new(&mut body);
+    let byte=req::db::list("db.hosts".to_string(),client.gettoken(),query);
+    byte.serialize(&mut serializer).unwrap();
+    send_async(&client,body).await
+}
+/// [`hosts`] decoded into [`Host`]s.
+pub async fn hosts_typed(client:Client,query:HostQuery) -> Result<Vec<Host>,E> {
+    let mut reply:HashMap<String,Vec<Host>>=hosts(client,query).await?;
+    Ok(reply.remove("hosts").unwrap_or_default())
+}
+pub async fn get_host<T:DOwned>(client:Client,workspace:Option<String>,host:&str) -> Result<T,E>  {
+    let mut body=Vec::new();
+    let mut serializer=Serializer::new(&mut body);
+    let mut hash:HashMap<String,String>=HashMap::new();
+    hash.insert("host".to_string(),host.to_string());
+    if let Some(val)=workspace {
+        hash.insert("workspace".to_string(),val);
+    }
+    let byte=req::db::grd_host("db.get_host".to_string(),client.gettoken(),hash);
+    byte.serialize(&mut serializer).unwrap();
+    send_async(&client,body).await
 }
 pub async fn report_host<T:DOwned>(client:Client,workspace:Option<String>,host:&str) -> Result<T,E> {
-    db::report_host(client,workspace,host)
+    let mut body=Vec::new();
+    let mut serializer=Serializer::new(&mut body);
+    let mut hash:HashMap<String,String>=HashMap::new();
+    hash.insert("host".to_string(),host.to_string());
+    if let Some(val)=workspace {
+        hash.insert("workspace".to_string(),val);
+    }
+    let byte=req::db::grd_host("db.report_host".to_string(),client.gettoken(),hash);
+    byte.serialize(&mut serializer).unwrap();
+    send_async(&client,body).await
 }
 pub async fn del_host<T:DOwned>(client:Client,workspace:Option<String>,host:&str) -> Result<T,E> {
-    db::del_host(client,workspace,host)
-}
-
-pub async fn services<T:DOwned>(client:Client,hash:HashMap<String,String>) -> Result<T,E> {
-    db::services(client,hash)
+    let mut body=Vec::new();
+    let mut serializer=Serializer::new(&mut body);
+    let mut hash:HashMap<String,String>=HashMap::new();
+    hash.insert("host".to_string(),host.to_string());
+    if let Some(val)=workspace {
+        hash.insert("workspace".to_string(),val);
+    }
+    let byte=req::db::grd_host("db.del_host".to_string(),client.gettoken(),hash);
+    byte.serialize(&mut serializer).unwrap();
+    send_async(&client,body).await
+}
+pub async fn services<T:DOwned>(client:Client,query:ServiceQuery) -> Result<T,E> {
+    let mut body=Vec::new();
+    let mut serializer=Serializer::new(&mut body);
+    let byte=req::db::list("db.services".to_string(),client.gettoken(),query);
+    byte.serialize(&mut serializer).unwrap();
+    send_async(&client,body).await
+}
+/// [`services`] decoded into [`Service`]s.
+pub async fn services_typed(client:Client,query:ServiceQuery) -> Result<Vec<Service>,E> {
+    let mut reply:HashMap<String,Vec<Service>>=services(client,query).await?;
+    Ok(reply.remove("services").unwrap_or_default())
 }
 pub async fn report_service<T:DOwned>(client:Client,hash:HashMap<String,String>) -> Result<T,E> {
-    db::report_service(client,hash)
+    let mut body=Vec::new();
+    let mut serializer=Serializer::new(&mut body);
+    let byte=req::db::grd_service("db.report_service".to_string(),client.gettoken(),hash);
+    byte.serialize(&mut serializer).unwrap();
+    send_async(&client,body).await
 }
 pub async fn get_service<T:DOwned>(client:Client,hash:HashMap<String,String>) -> Result<T,E> {
-    db::get_service(client,hash)
+    let mut body=Vec::new();
+    let mut serializer=Serializer::new(&mut body);
+    let byte=req::db::grd_service("db.get_service".to_string(),client.gettoken(),hash);
+    byte.serialize(&mut serializer).unwrap();
+    send_async(&client,body).await
 }
 pub async fn del_service<T:DOwned>(client:Client,hash:HashMap<String,String>) -> Result<T,E> {
-    db::del_service(client,hash)
+    let mut body=Vec::new();
+    let mut serializer=Serializer::new(&mut body);
+    let byte=req::db::grd_service("db.del_service".to_string(),client.gettoken(),hash);
+    byte.serialize(&mut serializer).unwrap();
+    send_async(&client,body).await
 }
 
-pub async fn vulns<T:DOwned>(client:Client,hash:HashMap<String,String>) -> Result<T,E> {
-    db::vulns(client,hash)
+pub async fn vulns<T:DOwned>(client:Client,query:VulnQuery) -> Result<T,E> {
+    let mut body=Vec::new();
+    let mut serializer=Serializer::new(&mut body);
+    let byte=req::db::list("db.vulns".to_string(),client.gettoken(),query);
+    byte.serialize(&mut serializer).unwrap();
+    send_async(&client,body).await
+}
+/// [`vulns`] decoded into [`Vuln`]s.
+pub async fn vulns_typed(client:Client,query:VulnQuery) -> Result<Vec<Vuln>,E> {
+    let mut reply:HashMap<String,Vec<Vuln>>=vulns(client,query).await?;
+    Ok(reply.remove("vulns").unwrap_or_default())
 }
 pub async fn del_vuln<T:DOwned>(client:Client,hash:HashMap<String,String>) -> Result<T,E> {
-    db::del_vuln(client,hash)
+    let mut body=Vec::new();
+    let mut serializer=Serializer::new(&mut body);
+    let byte=req::db::grd_vuln("db.del_vuln".to_string(),client.gettoken(),hash);
+    byte.serialize(&mut serializer).unwrap();
+    send_async(&client,body).await
 }
 pub async fn report_vuln<T:DOwned>(client:Client,hash:HashMap<String,String>) -> Result<T,E> {
-    db::report_vuln(client,hash)
+    let mut body=Vec::new();
+    let mut serializer=Serializer::new(&mut body);
+    let byte=req::db::grd_vuln("db.report_vuln".to_string(),client.gettoken(),hash);
+    byte.serialize(&mut serializer).unwrap();
+    send_async(&client,body).await
 }
 pub async fn get_vuln<T:DOwned>(client:Client,hash:HashMap<String,String>) -> Result<T,E> {
-    db::get_vuln(client,hash)
+    let mut body=Vec::new();
+    let mut serializer=Serializer::new(&mut body);
+    let byte=req::db::grd_vuln("db.get_vuln".to_string(),client.gettoken(),hash);
+    byte.serialize(&mut serializer).unwrap();
+    send_async(&client,body).await
 }
 
 pub async fn workspaces<T:DOwned>(client:Client) -> Result<T,E> {
-    db::workspaces(client)
+    let mut body=Vec::new();
+    let mut serializer=Serializer::new(&mut body);
+    let byte=req::db::workspaces("db.workspaces".to_string(),client.gettoken());
+    byte.serialize(&mut serializer).unwrap();
+    send_async(&client,body).await
 }
 pub async fn current_workspace<T:DOwned>(client:Client) -> Result<T,E> {
-    db::current_workspace(client)
+    let mut body=Vec::new();
+    let mut serializer=Serializer::new(&mut body);
+    let byte=req::db::current_workspace("db.current_workspace".to_string(),client.gettoken());
+    byte.serialize(&mut serializer).unwrap();
+    send_async(&client,body).await
 }
 pub async fn get_workspace<T:DOwned>(client:Client,workspace:&str) -> Result<T,E> {
-    db::get_workspace(client,workspace)
+    let mut body=Vec::new();
+    let mut serializer=Serializer::new(&mut body);
+    let byte=req::db::gsda_workspace("db.get_workspace".to_string(),client.gettoken(),workspace.to_string());
+    byte.serialize(&mut serializer).unwrap();
+    send_async(&client,body).await
 }
 pub async fn set_workspace<T:DOwned>(client:Client,workspace:&str) -> Result<T,E> {
-    db::set_workspace(client,workspace)
-}
-pub async fn del_workspace<T:DOwned>(client:Client,workspace:&str) -> Result<T,E>  {
-    db::del_workspace(client,workspace)
-}
-pub async fn add_workspace<T:DOwned>(client:Client,workspace:&str) -> Result<T,E>  {
-    db::add_workspace(client,workspace)
+    let mut body=Vec::new();
+    let mut serializer=Serializer::new(&mut body);
+    let byte=req::db::gsda_workspace("db.set_workspace".to_string(),client.gettoken(),workspace.to_string());
+    byte.serialize(&mut serializer).unwrap();
+    send_async(&client,body).await
+}
+pub async fn del_workspace<T:DOwned>(client:Client,workspace:&str) -> Result<T,E> {
+    let mut body=Vec::new();
+    let mut serializer=Serializer::new(&mut body);
+    let byte=req::db::gsda_workspace("db.del_workspace".to_string(),client.gettoken(),workspace.to_string());
+    byte.serialize(&mut serializer).unwrap();
+    send_async(&client,body).await
+}
+pub async fn add_workspace<T:DOwned>(client:Client,workspace:&str) -> Result<T,E> {
+    let mut body=Vec::new();
+    let mut serializer=Serializer::new(&mut body);
+    let byte=req::db::gsda_workspace("db.add_workspace".to_string(),client.gettoken(),workspace.to_string());
+    byte.serialize(&mut serializer).unwrap();
+    send_async(&client,body).await
 }
 
 pub async fn get_note<T:DOwned>(client:Client,hash:HashMap<String,String>) -> Result<T,E> {
-    db::get_note(client,hash)
+    let mut body=Vec::new();
+    let mut serializer=Serializer::new(&mut body);
+    let byte=req::db::grd_note("db.get_note".to_string(),client.gettoken(),hash);
+    byte.serialize(&mut serializer).unwrap();
+    send_async(&client,body).await
 }
 pub async fn report_note<T:DOwned>(client:Client,hash:HashMap<String,String>) -> Result<T,E> {
-    db::report_note(client,hash)
-}
-pub async fn notes<T:DOwned>(client:Client,hash:HashMap<String,String>) -> Result<T,E> {
-    db::notes(client,hash)
+    let mut body=Vec::new();
+    let mut serializer=Serializer::new(&mut body);
+    let byte=req::db::grd_note("db.report_note".to_string(),client.gettoken(),hash);
+    byte.serialize(&mut serializer).unwrap();
+    send_async(&client,body).await
+}
+pub async fn notes<T:DOwned>(client:Client,query:NoteQuery) -> Result<T,E> {
+    let mut body=Vec::new();
+    let mut serializer=Serializer::new(&mut body);
+    let byte=req::db::list("db.notes".to_string(),client.gettoken(),query);
+    byte.serialize(&mut serializer).unwrap();
+    send_async(&client,body).await
+}
+/// [`notes`] decoded into [`Note`]s.
+pub async fn notes_typed(client:Client,query:NoteQuery) -> Result<Vec<Note>,E> {
+    let mut reply:HashMap<String,Vec<Note>>=notes(client,query).await?;
+    Ok(reply.remove("notes").unwrap_or_default())
 }
 pub async fn del_note<T:DOwned>(client:Client,hash:HashMap<String,String>) -> Result<T,E> {
-    db::del_note(client,hash)
+    let mut body=Vec::new();
+    let mut serializer=Serializer::new(&mut body);
+    let byte=req::db::grd_note("db.del_note".to_string(),client.gettoken(),hash);
+    byte.serialize(&mut serializer).unwrap();
+    send_async(&client,body).await
 }
 
 pub async fn get_client<T:DOwned>(client:Client,hash:HashMap<String,String>) -> Result<T,E> {
-    db::get_client(client,hash)
-}
-pub async fn clients<T:DOwned>(client:Client,hash:HashMap<String,String>) -> Result<T,E> {
-    db::clients(client,hash)
+    let mut body=Vec::new();
+    let mut serializer=Serializer::new(&mut body);
+    let byte=req::db::grd_client("db.get_client".to_string(),client.gettoken(),hash);
+    byte.serialize(&mut serializer).unwrap();
+    send_async(&client,body).await
+}
+pub async fn clients<T:DOwned>(client:Client,query:ClientQuery) -> Result<T,E> {
+    let mut body=Vec::new();
+    let mut serializer=Serializer::new(&mut body);
+    let byte=req::db::list("db.clients".to_string(),client.gettoken(),query);
+    byte.serialize(&mut serializer).unwrap();
+    send_async(&client,body).await
+}
+/// [`clients`] decoded into [`WebClient`]s.
+pub async fn clients_typed(client:Client,query:ClientQuery) -> Result<Vec<WebClient>,E> {
+    let mut reply:HashMap<String,Vec<WebClient>>=clients(client,query).await?;
+    Ok(reply.remove("clients").unwrap_or_default())
 }
 pub async fn del_client<T:DOwned>(client:Client,hash:HashMap<String,String>) -> Result<T,E> {
-    db::del_client(client,hash)
+    let mut body=Vec::new();
+    let mut serializer=Serializer::new(&mut body);
+    let byte=req::db::grd_client("db.del_client".to_string(),client.gettoken(),hash);
+    byte.serialize(&mut serializer).unwrap();
+    send_async(&client,body).await
 }
 pub async fn report_client<T:DOwned>(client:Client,hash:HashMap<String,String>) -> Result<T,E> {
-    db::report_client(client,hash)
+    let mut body=Vec::new();
+    let mut serializer=Serializer::new(&mut body);
+    let byte=req::db::grd_client("db.report_client".to_string(),client.gettoken(),hash);
+    byte.serialize(&mut serializer).unwrap();
+    send_async(&client,body).await
 }
 
 pub async fn get_ref<T:DOwned>(client:Client,ref_name:&str) -> Result<T,E> {
-    db::get_ref(client,ref_name)
+    let mut body=Vec::new();
+    let mut serializer=Serializer::new(&mut body);
+    let byte=req::db::get_ref("db.get_ref".to_string(),client.gettoken(),ref_name.to_string());
+    byte.serialize(&mut serializer).unwrap();
+    send_async(&client,body).await
 }
 
-pub async fn events<T:DOwned>(client:Client,hash:HashMap<String,String>) -> Result<T,E> {
-    db::events(client,hash)
+pub async fn events<T:DOwned>(client:Client,query:ListQuery) -> Result<T,E> {
+    let mut body=Vec::new();
+    let mut serializer=Serializer::new(&mut body);
+    let byte=req::db::list("db.events".to_string(),client.gettoken(),query);
+    byte.serialize(&mut serializer).unwrap();
+    send_async(&client,body).await
+}
+/// [`events`] decoded into [`Event`]s.
+pub async fn events_typed(client:Client,query:ListQuery) -> Result<Vec<Event>,E> {
+    let mut reply:HashMap<String,Vec<Event>>=events(client,query).await?;
+    Ok(reply.remove("events").unwrap_or_default())
 }
 pub async fn report_event<T:DOwned>(client:Client,hash:HashMap<String,String>) -> Result<T,E> {
-    db::report_event(client,hash)
+    let mut body=Vec::new();
+    let mut serializer=Serializer::new(&mut body);
+    let byte=req::db::report_event("db.report_event".to_string(),client.gettoken(),hash);
+    byte.serialize(&mut serializer).unwrap();
+    send_async(&client,body).await
 }
 
 pub async fn report_loot<T:DOwned>(client:Client,hash:HashMap<String,String>) -> Result<T,E> {
-    db::report_loot(client,hash)
-}
-pub async fn loots<T:DOwned>(client:Client,hash:HashMap<String,String>) -> Result<T,E> {
-    db::loots(client,hash)
+    let mut body=Vec::new();
+    let mut serializer=Serializer::new(&mut body);
+    let byte=req::db::report_loot("db.report_loot".to_string(),client.gettoken(),hash);
+    byte.serialize(&mut serializer).unwrap();
+    send_async(&client,body).await
+}
+pub async fn loots<T:DOwned>(client:Client,query:ListQuery) -> Result<T,E> {
+    let mut body=Vec::new();
+    let mut serializer=Serializer::new(&mut body);
+    let byte=req::db::list("db.loots".to_string(),client.gettoken(),query);
+    byte.serialize(&mut serializer).unwrap();
+    send_async(&client,body).await
+}
+/// [`loots`] decoded into [`Loot`]s.
+pub async fn loots_typed(client:Client,query:ListQuery) -> Result<Vec<Loot>,E> {
+    let mut reply:HashMap<String,Vec<Loot>>=loots(client,query).await?;
+    Ok(reply.remove("loots").unwrap_or_default())
 }
 
-pub async fn creds<T:DOwned>(client:Client,hash:HashMap<String,String>) -> Result<T,E> {
-    db::creds(client,hash)
+pub async fn creds<T:DOwned>(client:Client,query:ListQuery) -> Result<T,E> {
+    let mut body=Vec::new();
+    let mut serializer=Serializer::new(&mut body);
+    let byte=req::db::list("db.creds".to_string(),client.gettoken(),query);
+    byte.serialize(&mut serializer).unwrap();
+    send_async(&client,body).await
+}
+/// [`creds`] decoded into [`Cred`]s.
+pub async fn creds_typed(client:Client,query:ListQuery) -> Result<Vec<Cred>,E> {
+    let mut reply:HashMap<String,Vec<Cred>>=creds(client,query).await?;
+    Ok(reply.remove("creds").unwrap_or_default())
 }
 
 pub async fn import_data<T:DOwned>(client:Client,data:&str) -> Result<T,E> {
-    db::import_data(client,data)
+    let mut body=Vec::new();
+    let mut serializer=Serializer::new(&mut body);
+    let byte=req::db::import_db("db.import_data".to_string(),client.gettoken(),data.to_string());
+    byte.serialize(&mut serializer).unwrap();
+    send_async(&client,body).await
+}
+pub async fn import_file<T:DOwned>(client:Client,mut file:File) -> Result<T,E> {
+    let mut body=Vec::new();
+    let mut serializer=Serializer::new(&mut body);
+    let mut data=String::new();
+    file.read_to_string(&mut data).unwrap();
+    let byte=req::db::import_db("db.import_data".to_string(),client.gettoken(),data);
+    byte.serialize(&mut serializer).unwrap();
+    send_async(&client,body).await
 }
-pub async fn import_file<T:DOwned>(client:Client,file:File) -> Result<T,E> {
-    db::import_file(client,file)
-}
-
 pub async fn set_driver<T:DOwned>(client:Client,driver:&str) -> Result<T,E> {
-    db::set_driver(client,driver)
+    let mut body=Vec::new();
+    let mut hash:HashMap<String,String>=HashMap::new();
+    let mut serializer=Serializer::new(&mut body);
+    hash.insert("driver".to_string(),driver.to_string());
+    let byte=req::db::driver("db.driver".to_string(),client.gettoken(),hash);
+    byte.serialize(&mut serializer).unwrap();
+    send_async(&client,body).await
 }
 pub async fn get_driver<T:DOwned>(client:Client) -> Result<T,E> {
-    db::get_driver(client)
+    let mut body=Vec::new();
+    let hash:HashMap<String,String>=HashMap::new();
+    let mut serializer=Serializer::new(&mut body);
+    let byte=req::db::driver("db.driver".to_string(),client.gettoken(),hash);
+    byte.serialize(&mut serializer).unwrap();
+    send_async(&client,body).await
 }
 
-pub async fn dbconnect<T:DOwned>(client:Client,driver:&str,hash:HashMap<String,String>) -> Result<T,E> {
-    db::dbconnect(client,driver,hash)
+pub async fn dbconnect<T:DOwned>(client:Client,driver:&str,mut hash:HashMap<String,String>) -> Result<T,E> {
+    let mut body=Vec::new();
+    hash.insert("driver".to_string(),driver.to_string());
+    let mut serializer=Serializer::new(&mut body);
+    let byte=req::db::connect("db.connect".to_string(),client.gettoken(),hash);
+    byte.serialize(&mut serializer).unwrap();
+    send_async(&client,body).await
 }
 
 pub async fn status<T:DOwned>(client:Client) -> Result<T,E> {
-    db::status(client)
+    let mut body=Vec::new();
+    let mut serializer=Serializer::new(&mut body);
+    let byte=req::db::status("db.status".to_string(),client.gettoken());
+    byte.serialize(&mut serializer).unwrap();
+    send_async(&client,body).await
 }
 
 pub async fn disconnect<T:DOwned>(client:Client) -> Result<T,E> {
-    db::disconnect(client)
+    let mut body=Vec::new();
+    let mut serializer=Serializer::new(&mut body);
+    let byte=req::db::disconnect("db.disconnect".to_string(),client.gettoken());
+    byte.serialize(&mut serializer).unwrap();
+    send_async(&client,body).await
+}
+
+type Page<T>=Pin<Box<dyn Future<Output=Result<Vec<T>,E>>+Send>>;
+
+/// Records of a database listing,fetched `page_size` at a time with `limit` and `offset` as they are consumed.
+///
+/// Returned by [`hosts_paged`] and the other `*_paged` functions.It is a [`Stream`],and [`Pages::next`] reads it
+/// without pulling in a stream extension trait.Only one page is held in memory,and dropping the stream stops the
+/// listing.A failed call is yielded once and ends the stream.
+pub struct Pages<T> {
+    fetch:Box<dyn FnMut(u32,u32) -> Page<T>+Send>,
+    pending:Option<Page<T>>,
+    page_size:u32,
+    offset:u32,
+    page:std::vec::IntoIter<T>,
+    done:bool,
+}
+
+// The records are only moved out of the buffered page,never pinned.
+impl<T> Unpin for Pages<T> {}
+
+impl<T> Pages<T> {
+    fn new(page_size:u32,fetch:Box<dyn FnMut(u32,u32) -> Page<T>+Send>) -> Self {
+        Pages {
+            fetch,
+            pending:None,
+            page_size:page_size.max(1),
+            offset:0,
+            page:Vec::new().into_iter(),
+            done:false,
+        }
+    }
+    /// The next record,or `None` once the listing is exhausted.
+    pub async fn next(&mut self) -> Option<Result<T,E>> {
+        poll_fn(|cx| Pin::new(&mut *self).poll_next(cx)).await
+    }
+}
+
+impl<T> Stream for Pages<T> {
+    type Item=Result<T,E>;
+    fn poll_next(self:Pin<&mut Self>,cx:&mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this=self.get_mut();
+        loop {
+            if let Some(record)=this.page.next() {
+                return Poll::Ready(Some(Ok(record)));
+            }
+            if this.done {
+                return Poll::Ready(None);
+            }
+            if this.pending.is_none() {
+                this.pending=Some((this.fetch)(this.offset,this.page_size));
+            }
+            let result=match this.pending.as_mut().unwrap().as_mut().poll(cx) {
+                Poll::Ready(result) => result,
+                Poll::Pending => return Poll::Pending,
+            };
+            this.pending=None;
+            match result {
+                Ok(page) => {
+                    this.done=page.len()<this.page_size as usize;
+                    this.offset+=page.len() as u32;
+                    this.page=page.into_iter();
+                },
+                Err(e) => {
+                    this.done=true;
+                    return Poll::Ready(Some(Err(e)));
+                },
+            }
+        }
+    }
+}
+
+/// [`hosts_typed`] page by page.The query's `limit` and `offset` are replaced by the paging.
+pub fn hosts_paged(client:Client,query:HostQuery,page_size:u32) -> Pages<Host> {
+    Pages::new(page_size,Box::new(move |offset,limit| Box::pin(hosts_typed(client.clone(),query.clone().offset(offset).limit(limit)))))
+}
+/// [`services_typed`] page by page.The query's `limit` and `offset` are replaced by the paging.
+pub fn services_paged(client:Client,query:ServiceQuery,page_size:u32) -> Pages<Service> {
+    Pages::new(page_size,Box::new(move |offset,limit| Box::pin(services_typed(client.clone(),query.clone().offset(offset).limit(limit)))))
+}
+/// [`vulns_typed`] page by page.The query's `limit` and `offset` are replaced by the paging.
+pub fn vulns_paged(client:Client,query:VulnQuery,page_size:u32) -> Pages<Vuln> {
+    Pages::new(page_size,Box::new(move |offset,limit| Box::pin(vulns_typed(client.clone(),query.clone().offset(offset).limit(limit)))))
+}
+/// [`notes_typed`] page by page.The query's `limit` and `offset` are replaced by the paging.
+pub fn notes_paged(client:Client,query:NoteQuery,page_size:u32) -> Pages<Note> {
+    Pages::new(page_size,Box::new(move |offset,limit| Box::pin(notes_typed(client.clone(),query.clone().offset(offset).limit(limit)))))
+}
+/// [`clients_typed`] page by page.The query's `limit` and `offset` are replaced by the paging.
+pub fn clients_paged(client:Client,query:ClientQuery,page_size:u32) -> Pages<WebClient> {
+    Pages::new(page_size,Box::new(move |offset,limit| Box::pin(clients_typed(client.clone(),query.clone().offset(offset).limit(limit)))))
+}
+/// [`events_typed`] page by page.The query's `limit` and `offset` are replaced by the paging.
+pub fn events_paged(client:Client,query:ListQuery,page_size:u32) -> Pages<Event> {
+    Pages::new(page_size,Box::new(move |offset,limit| Box::pin(events_typed(client.clone(),query.clone().offset(offset).limit(limit)))))
+}
+/// [`loots_typed`] page by page.The query's `limit` and `offset` are replaced by the paging.
+pub fn loots_paged(client:Client,query:ListQuery,page_size:u32) -> Pages<Loot> {
+    Pages::new(page_size,Box::new(move |offset,limit| Box::pin(loots_typed(client.clone(),query.clone().offset(offset).limit(limit)))))
+}
+/// [`creds_typed`] page by page.The query's `limit` and `offset` are replaced by the paging.
+pub fn creds_paged(client:Client,query:ListQuery,page_size:u32) -> Pages<Cred> {
+    Pages::new(page_size,Box::new(move |offset,limit| Box::pin(creds_typed(client.clone(),query.clone().offset(offset).limit(limit)))))
+}
+
+/// A database workspace,opened with [`Client::workspace`].
+///
+/// Every call made through the handle names the workspace,so it never depends on or changes the server's current
+/// workspace,which is shared by everyone using the same msfrpcd.
+#[derive(Debug,Clone)]
+pub struct Workspace {
+    client:Client,
+    name:String,
+}
+
+impl Workspace {
+    /// Creates the workspace unless it exists.
+    pub(crate) async fn open(client:Client,name:&str) -> Result<Self,E> {
+        let _:Value=add_workspace(client.clone(),name).await?;
+        Ok(Workspace {
+            client,
+            name:name.to_string(),
+        })
+    }
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    fn scoped(&self,mut hash:HashMap<String,String>) -> HashMap<String,String> {
+        hash.insert("workspace".to_string(),self.name.clone());
+        hash
+    }
+    pub async fn hosts_typed(&self,query:HostQuery) -> Result<Vec<Host>,E> {
+        hosts_typed(self.client.clone(),query.workspace(&self.name)).await
+    }
+    pub fn hosts_paged(&self,query:HostQuery,page_size:u32) -> Pages<Host> {
+        hosts_paged(self.client.clone(),query.workspace(&self.name),page_size)
+    }
+    pub async fn get_host<T:DOwned>(&self,host:&str) -> Result<T,E> {
+        get_host(self.client.clone(),Some(self.name.clone()),host).await
+    }
+    pub async fn report_host<T:DOwned>(&self,host:&str) -> Result<T,E> {
+        report_host(self.client.clone(),Some(self.name.clone()),host).await
+    }
+    pub async fn del_host<T:DOwned>(&self,host:&str) -> Result<T,E> {
+        del_host(self.client.clone(),Some(self.name.clone()),host).await
+    }
+    pub async fn services_typed(&self,query:ServiceQuery) -> Result<Vec<Service>,E> {
+        services_typed(self.client.clone(),query.workspace(&self.name)).await
+    }
+    pub fn services_paged(&self,query:ServiceQuery,page_size:u32) -> Pages<Service> {
+        services_paged(self.client.clone(),query.workspace(&self.name),page_size)
+    }
+    pub async fn get_service<T:DOwned>(&self,hash:HashMap<String,String>) -> Result<T,E> {
+        get_service(self.client.clone(),self.scoped(hash)).await
+    }
+    pub async fn report_service<T:DOwned>(&self,hash:HashMap<String,String>) -> Result<T,E> {
+        report_service(self.client.clone(),self.scoped(hash)).await
+    }
+    pub async fn del_service<T:DOwned>(&self,hash:HashMap<String,String>) -> Result<T,E> {
+        del_service(self.client.clone(),self.scoped(hash)).await
+    }
+    pub async fn vulns_typed(&self,query:VulnQuery) -> Result<Vec<Vuln>,E> {
+        vulns_typed(self.client.clone(),query.workspace(&self.name)).await
+    }
+    pub fn vulns_paged(&self,query:VulnQuery,page_size:u32) -> Pages<Vuln> {
+        vulns_paged(self.client.clone(),query.workspace(&self.name),page_size)
+    }
+    pub async fn get_vuln<T:DOwned>(&self,hash:HashMap<String,String>) -> Result<T,E> {
+        get_vuln(self.client.clone(),self.scoped(hash)).await
+    }
+    pub async fn report_vuln<T:DOwned>(&self,hash:HashMap<String,String>) -> Result<T,E> {
+        report_vuln(self.client.clone(),self.scoped(hash)).await
+    }
+    pub async fn del_vuln<T:DOwned>(&self,hash:HashMap<String,String>) -> Result<T,E> {
+        del_vuln(self.client.clone(),self.scoped(hash)).await
+    }
+    pub async fn notes_typed(&self,query:NoteQuery) -> Result<Vec<Note>,E> {
+        notes_typed(self.client.clone(),query.workspace(&self.name)).await
+    }
+    pub fn notes_paged(&self,query:NoteQuery,page_size:u32) -> Pages<Note> {
+        notes_paged(self.client.clone(),query.workspace(&self.name),page_size)
+    }
+    pub async fn get_note<T:DOwned>(&self,hash:HashMap<String,String>) -> Result<T,E> {
+        get_note(self.client.clone(),self.scoped(hash)).await
+    }
+    pub async fn report_note<T:DOwned>(&self,hash:HashMap<String,String>) -> Result<T,E> {
+        report_note(self.client.clone(),self.scoped(hash)).await
+    }
+    pub async fn del_note<T:DOwned>(&self,hash:HashMap<String,String>) -> Result<T,E> {
+        del_note(self.client.clone(),self.scoped(hash)).await
+    }
+    pub async fn loots_typed(&self,query:ListQuery) -> Result<Vec<Loot>,E> {
+        loots_typed(self.client.clone(),query.workspace(&self.name)).await
+    }
+    pub fn loots_paged(&self,query:ListQuery,page_size:u32) -> Pages<Loot> {
+        loots_paged(self.client.clone(),query.workspace(&self.name),page_size)
+    }
+    pub async fn report_loot<T:DOwned>(&self,hash:HashMap<String,String>) -> Result<T,E> {
+        report_loot(self.client.clone(),self.scoped(hash)).await
+    }
+    pub async fn creds_typed(&self,query:ListQuery) -> Result<Vec<Cred>,E> {
+        creds_typed(self.client.clone(),query.workspace(&self.name)).await
+    }
+    pub fn creds_paged(&self,query:ListQuery,page_size:u32) -> Pages<Cred> {
+        creds_paged(self.client.clone(),query.workspace(&self.name),page_size)
+    }
 }
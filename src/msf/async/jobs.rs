@@ -1,14 +1,30 @@
 use crate::client::Client;
+use crate::connect::send_async;
 use crate::error::Error as E;
-#[path="../blocking/jobs.rs"] mod jobs;
-use serde::de::DeserializeOwned as DOwned;
+use crate::structs::request as req;
+use rmp_serde::Serializer;
+use serde::{Serialize,de::DeserializeOwned as DOwned};
 
 pub async fn list<T:DOwned>(client:Client) -> Result<T,E> {
-    jobs::list(client.clone())
+    let mut body=Vec::new();
+    let mut se=Serializer::new(&mut body);
+    let byte=req::jobs::list("job.list".to_string(),client.gettoken());
+    byte.serialize(&mut se).unwrap();
+    send_async(&client,body).await
 }
 pub async fn info<T:DOwned>(client:Client,jobidstr:&str) -> Result<T,E> {
-    jobs::info(client.clone(),jobidstr)
+    let jobid:String=jobidstr.to_string();
+    let mut body=Vec::new();
+    let mut se=Serializer::new(&mut body);
+    let byte=req::jobs::info("job.info".to_string(),client.gettoken(),jobid);
+    byte.serialize(&mut se).unwrap();
+    send_async(&client,body).await
 }
 pub async fn stop<T:DOwned>(client:Client,jobidstr:&str) -> Result<T,E> {
-    jobs::stop(client.clone(),jobidstr)
+    let jobid:String=jobidstr.to_string();
+    let mut body=Vec::new();
+    let mut se=Serializer::new(&mut body);
+    let byte=req::jobs::stop("job.stop".to_string(),client.gettoken(),jobid);
+    byte.serialize(&mut se).unwrap();
+    send_async(&client,body).await
 }
@@ -1,10 +1,13 @@
 #![allow(non_camel_case_types)]
-#![allow(unused_assignments)]
 use crate::client::Client;
+use crate::connect::send_async;
 use std::collections::HashMap;
 use crate::error::Error as E;
-use serde::de::DeserializeOwned as DOwned;
-#[path="../blocking/modules.rs"] mod modules;
+use crate::structs::request as req;
+use crate::structs::response::modules::{ModuleInfo,ModuleOption};
+use std::collections::BTreeMap;
+use serde::{Serialize,de::DeserializeOwned as DOwned};
+use rmp_serde::Serializer;
 
 pub struct compactible {
     pub name:String,
@@ -16,63 +19,131 @@ pub struct list {
 impl list {
     pub fn new(client:Client) -> Self {
         list {
-            client:client,
+            client,
         }
     }
+    fn serialize(&self,method:&str,body:&mut Vec<u8>) {
+        let mut se=Serializer::new(body);
+        let byte=req::modules::list(method.to_string(),self.client.gettoken());
+        byte.serialize(&mut se).unwrap();
+    }
     pub async fn exploits<T:DOwned>(&self) -> Result<T,E> {
-        let list=modules::list::new(self.client.clone());
-        list.exploits()
+        let mut body=Vec::new();
+        self.serialize("module.exploits",&mut body);
+        send_async(&self.client,body).await
     }
     pub async fn auxiliary<T:DOwned>(&self) -> Result<T,E> {
-        let list=modules::list::new(self.client.clone());
-        list.auxiliary()
+        let mut body=Vec::new();
+        self.serialize("module.auxiliary",&mut body);
+        send_async(&self.client,body).await
     }
     pub async fn post<T:DOwned>(&self) -> Result<T,E> {
-        let list=modules::list::new(self.client.clone());
-        list.post()
+        let mut body=Vec::new();
+        self.serialize("module.post",&mut body);
+        send_async(&self.client,body).await
     }
     pub async fn payloads<T:DOwned>(&self) -> Result<T,E> {
-        let list=modules::list::new(self.client.clone());
-        list.payloads()
+        let mut body=Vec::new();
+        self.serialize("module.payloads",&mut body);
+        send_async(&self.client,body).await
     }
     pub async fn encoders<T:DOwned>(&self) -> Result<T,E> {
-        let list=modules::list::new(self.client.clone());
-        list.encoders()
+        let mut body=Vec::new();
+        self.serialize("module.encoders",&mut body);
+        send_async(&self.client,body).await
     }
     pub async fn nops<T:DOwned>(&self) -> Result<T,E> {
-        let list=modules::list::new(self.client.clone());
-        list.nops()
+        let mut body=Vec::new();
+        self.serialize("module.nops",&mut body);
+        send_async(&self.client,body).await
     }
 }
 pub async fn info<T:DOwned>(client:Client,moduletypestr:&str,modulenamestr:&str) -> Result<T,E> {
-    modules::info(client.clone(),moduletypestr,modulenamestr)
+    let moduletype:String=moduletypestr.to_string();
+    let modulename:String=modulenamestr.to_string();
+    let mut body=Vec::new();
+    let mut se=Serializer::new(&mut body);
+    let byte=req::modules::info("module.info".to_string(),client.gettoken(),moduletype,modulename);
+    byte.serialize(&mut se).unwrap();
+    send_async(&client,body).await
+}
+/// [`info`] decoded into [`ModuleInfo`].
+pub async fn info_typed(client:Client,moduletypestr:&str,modulenamestr:&str) -> Result<ModuleInfo,E> {
+    info(client,moduletypestr,modulenamestr).await
 }
 impl compactible {
     pub fn new(modulename:String,client:Client) -> Self {
         compactible {
             name:modulename,
-            client:client,
+            client,
         }
     }
     pub async fn payload<T:DOwned>(&self) -> Result<T,E> {
-        let list=modules::compactible::new(self.name.clone(),self.client.clone());
-        list.payload()
+        let mut body=Vec::new();
+        let mut se=Serializer::new(&mut body);
+        let byte=req::modules::compactible("module.compatible_payloads".to_string(),self.client.gettoken(),self.name.clone());
+        byte.serialize(&mut se).unwrap();
+        send_async(&self.client,body).await
     }
     pub async fn target_payloads<T:DOwned>(&self,targetindx:i32) -> Result<T,E> {
-        let list=modules::compactible::new(self.name.clone(),self.client.clone());
-        list.target_payloads(targetindx)
+        let mut body=Vec::new();
+        let mut se=Serializer::new(&mut body);
+        let byte=req::modules::compactible_tp("module.target_compatible_payloads".to_string(),self.client.gettoken(),self.name.clone(),targetindx);
+        byte.serialize(&mut se).unwrap();
+        send_async(&self.client,body).await
     }
     pub async fn sessions<T:DOwned>(&self) -> Result<T,E> {
-        let list=modules::compactible::new(self.name.clone(),self.client.clone());
-        list.sessions()
+        let mut body=Vec::new();
+        let mut se=Serializer::new(&mut body);
+        let byte=req::modules::compactible("module.compatible_sessions".to_string(),self.client.gettoken(),self.name.clone());
+        byte.serialize(&mut se).unwrap();
+        send_async(&self.client,body).await
     }
 }
 pub async fn option<T:DOwned>(client:Client,moduletypestr:&str,modulenamestr:&str) -> Result<T,E> {
-    modules::option(client.clone(),moduletypestr,modulenamestr)
+    let moduletype:String=moduletypestr.to_string();
+    let modulename:String=modulenamestr.to_string();
+    let mut body=Vec::new();
+    let mut serializer=Serializer::new(&mut body);
+    let byte=req::modules::options("module.options".to_string(),client.gettoken(),moduletype,modulename);
+    byte.serialize(&mut serializer).unwrap();
+    send_async(&client,body).await
+}
+/// [`option`] decoded into [`ModuleOption`]s by option name.
+pub async fn option_typed(client:Client,moduletypestr:&str,modulenamestr:&str) -> Result<BTreeMap<String,ModuleOption>,E> {
+    option(client,moduletypestr,modulenamestr).await
+}
+/// The formats `msfvenom -f` can wrap a payload in,e.g. `exe` or `elf`.
+pub async fn executable_formats<T:DOwned>(client:Client) -> Result<T,E> {
+    let mut body=Vec::new();
+    let mut se=Serializer::new(&mut body);
+    let byte=req::modules::list("module.executable_formats".to_string(),client.gettoken());
+    byte.serialize(&mut se).unwrap();
+    send_async(&client,body).await
+}
+/// The formats `msfvenom -f` can render a payload as source in,e.g. `c` or `python`.
+pub async fn transform_formats<T:DOwned>(client:Client) -> Result<T,E> {
+    let mut body=Vec::new();
+    let mut se=Serializer::new(&mut body);
+    let byte=req::modules::list("module.transform_formats".to_string(),client.gettoken());
+    byte.serialize(&mut se).unwrap();
+    send_async(&client,body).await
 }
 pub async fn encoder<T:DOwned>(client:Client,datastr:&str,encodermodulestr:&str,options:HashMap<String,String>) -> Result<T,E> {
-    modules::encoder(client.clone(),datastr,encodermodulestr,options)
+    let data:String=datastr.to_string();
+    let encodermodule:String=encodermodulestr.to_string();
+    let mut body=Vec::new();
+    let mut se=Serializer::new(&mut body);
+    let byte=req::modules::encoder("module.encode".to_string(),client.gettoken(),data,encodermodule,options);
+    byte.serialize(&mut se).unwrap();
+    send_async(&client,body).await
 }
 pub async fn execute<T:DOwned>(client:Client,moduletypestr:&str,modulenamestr:&str,options:HashMap<String,String>) -> Result<T,E> {
-    modules::execute(client.clone(),moduletypestr,modulenamestr,options)
+    let moduletype:String=moduletypestr.to_string();
+    let modulename:String=modulenamestr.to_string();
+    let mut body=Vec::new();
+    let mut se=Serializer::new(&mut body);
+    let byte=req::modules::execute("module.execute".to_string(),client.gettoken(),moduletype,modulename,options);
+    byte.serialize(&mut se).unwrap();
+    send_async(&client,body).await
 }
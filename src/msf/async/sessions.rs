@@ -1,22 +1,59 @@
 #![allow(non_camel_case_types)]
 use crate::client::Client;
+use crate::connect::send_async;
 use crate::error::Error as E;
-use serde::de::DeserializeOwned as DOwned;
-#[path="../blocking/sessions.rs"] mod sessions;
+use crate::structs::request as req;
+use crate::structs::response::IdMap;
+use crate::structs::response::sessions::SessionInfo;
+use std::collections::BTreeMap;
+use serde::{Serialize,de::DeserializeOwned as DOwned};
+use rmp_serde::Serializer;
 
 pub async fn list<T:DOwned>(client:Client) -> Result<T,E> {
-    sessions::list(client.clone())
+    let mut body=Vec::new();
+    let mut se=Serializer::new(&mut body);
+    let byte=req::sessions::list("session.list".to_string(),client.gettoken());
+    byte.serialize(&mut se).unwrap();
+    send_async(&client,body).await
+}
+/// [`list`] decoded into [`SessionInfo`]s by session id.
+pub async fn list_typed(client:Client) -> Result<BTreeMap<u32,SessionInfo>,E> {
+    list::<IdMap<SessionInfo>>(client).await.map(|sessions| sessions.0)
 }
 pub async fn stop<T:DOwned>(client:Client,sessionidstr:&str) -> Result<T,E> {
-    sessions::stop(client.clone(),sessionidstr)
+    let sessionid:String=sessionidstr.to_string();
+    let mut body=Vec::new();
+    let mut se=Serializer::new(&mut body);
+    let byte=req::sessions::stop("session.stop".to_string(),client.gettoken(),sessionid);
+    byte.serialize(&mut se).unwrap();
+    send_async(&client,body).await
 }
 pub struct shell;
 impl shell {
     pub async fn read<T:DOwned>(client:Client,sessionidstr:&str,readpointer:Option<i32>) -> Result<T,E> {
-        sessions::shell::read(client.clone(),sessionidstr,readpointer)
+        let sessionid:String=sessionidstr.to_string();
+        let mut body=Vec::new();
+        let mut se=Serializer::new(&mut body);
+        match readpointer {
+            Some(pointer) => {
+                let byte=req::sessions::shell_read_with_pointer("session.shell_read".to_string(),client.gettoken(),sessionid,pointer);
+                byte.serialize(&mut se).unwrap();
+            },
+            None => {
+                let byte=req::sessions::shell_read("session.shell_read".to_string(),client.gettoken(),sessionid);
+                byte.serialize(&mut se).unwrap();
+            },
+        }
+        send_async(&client,body).await
     }
     pub async fn write<T:DOwned>(client:Client,sessionidstr:&str,datastr:&str) -> Result<T,E> {
-        sessions::shell::write(client.clone(),sessionidstr,datastr)
+        let sessionid:String=sessionidstr.to_string();
+        let data:String=datastr.to_string();
+        let mut body=Vec::new();
+        let mut se=Serializer::new(&mut body);
+        let byte=req::sessions::shell_write("session.shell_write".to_string(),client.gettoken(),sessionid,data);
+        byte.serialize(&mut se).unwrap();
+        send_async(&client,body).await
     }
 }
 pub struct meterpreter {
@@ -27,44 +64,71 @@ impl meterpreter {
     pub fn new(client:Client,sessionidstr:&str) -> Self {
         meterpreter {
             sessionid:sessionidstr.to_string(),
-            client:client,
+            client,
+        }
+    }
+    fn serialize(&self,body:&mut Vec<u8>,method:&str,param:Option<String>) {
+        let mut se=Serializer::new(body);
+        match param {
+            Some(val) => {
+                let byte=req::sessions::meterpreter_with_two(method.to_string(),self.client.gettoken(),self.sessionid.clone(),val);
+                byte.serialize(&mut se).unwrap();
+            },
+            None => {
+                let byte=req::sessions::meterpreter_with_one(method.to_string(),self.client.gettoken(),self.sessionid.clone());
+                byte.serialize(&mut se).unwrap();
+            },
         }
     }
     pub async fn write<T:DOwned>(&self,datastr:&str) -> Result<T,E> {
-        let mtpr=sessions::meterpreter::new(self.client.clone(),&self.sessionid);
-        mtpr.write(datastr)
+        let mut body=Vec::new();
+        self.serialize(&mut body,"session.meterpreter_write",Some(datastr.to_string()));
+        send_async(&self.client,body).await
     }
     pub async fn read<T:DOwned>(&self) -> Result<T,E> {
-        let mtpr=sessions::meterpreter::new(self.client.clone(),&self.sessionid);
-        mtpr.read()
+        let mut body=Vec::new();
+        self.serialize(&mut body,"session.meterpreter_read",None);
+        send_async(&self.client,body).await
     }
     pub async fn run_single<T:DOwned>(&self,commandstr:&str) -> Result<T,E> {
-        let mtpr=sessions::meterpreter::new(self.client.clone(),&self.sessionid);
-        mtpr.run_single(commandstr)
+        let mut body=Vec::new();
+        self.serialize(&mut body,"session.meterpreter_run_single",Some(commandstr.to_string()));
+        send_async(&self.client,body).await
     }
     pub async fn script<T:DOwned>(&self,scriptnamestr:&str) -> Result<T,E> {
-        let mtpr=sessions::meterpreter::new(self.client.clone(),&self.sessionid);
-        mtpr.script(scriptnamestr)
+        let mut body=Vec::new();
+        self.serialize(&mut body,"session.meterpreter_script",Some(scriptnamestr.to_string()));
+        send_async(&self.client,body).await
     }
     pub async fn detach_session<T:DOwned>(&self) -> Result<T,E> {
-        let mtpr=sessions::meterpreter::new(self.client.clone(),&self.sessionid);
-        mtpr.detach_session()
+        let mut body=Vec::new();
+        self.serialize(&mut body,"session.meterpreter_session_detach",None);
+        send_async(&self.client,body).await
     }
     pub async fn kill_session<T:DOwned>(&self) -> Result<T,E> {
-        let mtpr=sessions::meterpreter::new(self.client.clone(),&self.sessionid);
-        mtpr.kill_session()
+        let mut body=Vec::new();
+        self.serialize(&mut body,"session.meterpreter_session_kill",None);
+        send_async(&self.client,body).await
     }
     pub async fn tabs<T:DOwned>(&self,inputlinestr:&str) -> Result<T,E> {
-        let mtpr=sessions::meterpreter::new(self.client.clone(),&self.sessionid);
-        mtpr.tabs(inputlinestr)
+        let mut body=Vec::new();
+        self.serialize(&mut body,"session.meterpreter_tabs",Some(inputlinestr.to_string()));
+        send_async(&self.client,body).await
     }
     pub async fn compactible_modules<T:DOwned>(&self) -> Result<T,E> {
-        let mtpr=sessions::meterpreter::new(self.client.clone(),&self.sessionid);
-        mtpr.compactible_modules()
+        let mut body=Vec::new();
+        self.serialize(&mut body,"session.compatible_modules",None);
+        send_async(&self.client,body).await
     }
 }
 pub async fn shell_upgrade<T:DOwned>(client:Client,sessionidstr:&str,connecthoststr:&str,connectport:i32) -> Result<T,E> {
-    sessions::shell_upgrade(client.clone(),sessionidstr,connecthoststr,connectport)
+    let sessionid:String=sessionidstr.to_string();
+    let connecthost:String=connecthoststr.to_string();
+    let mut body=Vec::new();
+    let mut se=Serializer::new(&mut body);
+    let byte=req::sessions::shell_upgrade("session.shell_upgrade".to_string(),client.gettoken(),sessionid,connecthost,connectport);
+    byte.serialize(&mut se).unwrap();
+    send_async(&client,body).await
 }
 pub struct ring {
     client:Client,
@@ -73,20 +137,36 @@ pub struct ring {
 impl ring {
     pub fn new(client:Client,sessionid:&str) -> Self {
         ring {
-            client:client,
+            client,
             sessionid:sessionid.to_string(),
         }
     }
+    fn serialize(&self,body:&mut Vec<u8>,method:&str,arg:Option<String>) {
+        let mut se=Serializer::new(body);
+        match arg {
+            Some(val) => {
+                let byte=req::sessions::ring_with_arg(method.to_string(),self.client.gettoken(),self.sessionid.clone(),val);
+                byte.serialize(&mut se).unwrap();
+            },
+            None => {
+                let byte=req::sessions::ring_without_arg(method.to_string(),self.client.gettoken(),self.sessionid.clone());
+                byte.serialize(&mut se).unwrap();
+            },
+        }
+    }
     pub async fn clear<T:DOwned>(&self) -> Result<T,E> {
-        let rng=sessions::ring::new(self.client.clone(),&self.sessionid);
-        rng.clear()
+        let mut body=Vec::new();
+        self.serialize(&mut body,"session.ring_clear",None);
+        send_async(&self.client,body).await
     }
     pub async fn last<T:DOwned>(&self) -> Result<T,E> {
-        let rng=sessions::ring::new(self.client.clone(),&self.sessionid);
-        rng.last()
+        let mut body=Vec::new();
+        self.serialize(&mut body,"session.ring_last",None);
+        send_async(&self.client,body).await
     }
     pub async fn put<T:DOwned>(&self,datastr:&str) -> Result<T,E> {
-        let rng=sessions::ring::new(self.client.clone(),&self.sessionid);
-        rng.put(datastr)
+        let mut body=Vec::new();
+        self.serialize(&mut body,"session.ring_put",Some(datastr.to_string()));
+        send_async(&self.client,body).await
     }
 }
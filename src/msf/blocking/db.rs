@@ -1,358 +1,545 @@
 #![allow(non_camel_case_types)]
-#[path="../../connect.rs"] mod connect;
-#[path="../../structs/mod.rs"] mod structs;
-use crate::error::{MsfError,Error as E};
-use structs::request as req;
+use crate::error::Error as E;
+use crate::structs::request as req;
 use crate::client::Client;
+use crate::value::Value;
+use crate::connect::send;
 use std::fs::File;
 use std::io::Read;
 use std::collections::HashMap;
 use serde::{Serialize,de::DeserializeOwned as DOwned};
-use rmp_serde::{Serializer,decode::Error as derror,from_read};
+use rmp_serde::Serializer;
+use crate::structs::query::{ClientQuery,HostQuery,ListQuery,NoteQuery,ServiceQuery,VulnQuery};
+use crate::structs::response::db::{Cred,Event,Host,Loot,Note,Service,Vuln,WebClient};
 
-pub fn hosts<T:DOwned>(client:Client) -> Result<T,E> {
+pub fn hosts<T:DOwned>(client:Client,query:HostQuery) -> Result<T,E> {
     let mut body=Vec::new();
     let mut serializer=Serializer::new(&mut body);
-    let byte=req::db::hosts("db.hosts".to_string(),client.token.as_ref().unwrap().to_string());
+    let byte=req::db::list("db.hosts".to_string(),client.gettoken(),query);
     byte.serialize(&mut serializer).unwrap();
-    r#return(client.url,body)
-
+    send(&client,body)
+}
+/// [`hosts`] decoded into [`Host`]s.
+pub fn hosts_typed(client:Client,query:HostQuery) -> Result<Vec<Host>,E> {
+    let mut reply:HashMap<String,Vec<Host>>=hosts(client,query)?;
+    Ok(reply.remove("hosts").unwrap_or_default())
 }
 pub fn get_host<T:DOwned>(client:Client,workspace:Option<String>,host:&str) -> Result<T,E>  {
     let mut body=Vec::new();
     let mut serializer=Serializer::new(&mut body);
     let mut hash:HashMap<String,String>=HashMap::new();
     hash.insert("host".to_string(),host.to_string());
-    match workspace {
-        Some(val) => {
-            hash.insert("workspace".to_string(),val);
-        },
-        None => {},
-    };
-    let byte=req::db::grd_host("db.get_host".to_string(),client.token.as_ref().unwrap().to_string(),hash);
+    if let Some(val)=workspace {
+        hash.insert("workspace".to_string(),val);
+    }
+    let byte=req::db::grd_host("db.get_host".to_string(),client.gettoken(),hash);
     byte.serialize(&mut serializer).unwrap();
-    r#return(client.url,body)
+    send(&client,body)
 }
 pub fn report_host<T:DOwned>(client:Client,workspace:Option<String>,host:&str) -> Result<T,E> {
     let mut body=Vec::new();
     let mut serializer=Serializer::new(&mut body);
     let mut hash:HashMap<String,String>=HashMap::new();
     hash.insert("host".to_string(),host.to_string());
-    match workspace {
-        Some(val) => {
-            hash.insert("workspace".to_string(),val);
-        },
-        None => {},
-    };
-    let byte=req::db::grd_host("db.report_host".to_string(),client.token.as_ref().unwrap().to_string(),hash);
+    if let Some(val)=workspace {
+        hash.insert("workspace".to_string(),val);
+    }
+    let byte=req::db::grd_host("db.report_host".to_string(),client.gettoken(),hash);
     byte.serialize(&mut serializer).unwrap();
-    r#return(client.url,body)
+    send(&client,body)
 }
 pub fn del_host<T:DOwned>(client:Client,workspace:Option<String>,host:&str) -> Result<T,E> {
     let mut body=Vec::new();
     let mut serializer=Serializer::new(&mut body);
     let mut hash:HashMap<String,String>=HashMap::new();
     hash.insert("host".to_string(),host.to_string());
-    match workspace {
-        Some(val) => {
-            hash.insert("workspace".to_string(),val);
-        },
-        None => {},
-    };
-    let byte=req::db::grd_host("db.del_host".to_string(),client.token.as_ref().unwrap().to_string(),hash);
+    if let Some(val)=workspace {
+        hash.insert("workspace".to_string(),val);
+    }
+    let byte=req::db::grd_host("db.del_host".to_string(),client.gettoken(),hash);
     byte.serialize(&mut serializer).unwrap();
-    r#return(client.url,body)
+    send(&client,body)
 }
-pub fn services<T:DOwned>(client:Client,hash:HashMap<String,String>) -> Result<T,E> {
+pub fn services<T:DOwned>(client:Client,query:ServiceQuery) -> Result<T,E> {
     let mut body=Vec::new();
     let mut serializer=Serializer::new(&mut body);
-    let byte=req::db::nc("db.services".to_string(),client.token.as_ref().unwrap().to_string(),hash);
+    let byte=req::db::list("db.services".to_string(),client.gettoken(),query);
     byte.serialize(&mut serializer).unwrap();
-    r#return(client.url,body)
+    send(&client,body)
+}
+/// [`services`] decoded into [`Service`]s.
+pub fn services_typed(client:Client,query:ServiceQuery) -> Result<Vec<Service>,E> {
+    let mut reply:HashMap<String,Vec<Service>>=services(client,query)?;
+    Ok(reply.remove("services").unwrap_or_default())
 }
 pub fn report_service<T:DOwned>(client:Client,hash:HashMap<String,String>) -> Result<T,E> {
     let mut body=Vec::new();
     let mut serializer=Serializer::new(&mut body);
-    let byte=req::db::grd_service("db.report_service".to_string(),client.token.as_ref().unwrap().to_string(),hash);
+    let byte=req::db::grd_service("db.report_service".to_string(),client.gettoken(),hash);
     byte.serialize(&mut serializer).unwrap();
-    r#return(client.url,body)
+    send(&client,body)
 }
 pub fn get_service<T:DOwned>(client:Client,hash:HashMap<String,String>) -> Result<T,E> {
     let mut body=Vec::new();
     let mut serializer=Serializer::new(&mut body);
-    let byte=req::db::grd_service("db.get_service".to_string(),client.token.as_ref().unwrap().to_string(),hash);
+    let byte=req::db::grd_service("db.get_service".to_string(),client.gettoken(),hash);
     byte.serialize(&mut serializer).unwrap();
-    r#return(client.url,body)
+    send(&client,body)
 }
 pub fn del_service<T:DOwned>(client:Client,hash:HashMap<String,String>) -> Result<T,E> {
     let mut body=Vec::new();
     let mut serializer=Serializer::new(&mut body);
-    let byte=req::db::grd_service("db.del_service".to_string(),client.token.as_ref().unwrap().to_string(),hash);
+    let byte=req::db::grd_service("db.del_service".to_string(),client.gettoken(),hash);
     byte.serialize(&mut serializer).unwrap();
-    r#return(client.url,body)
+    send(&client,body)
 }
 
-pub fn vulns<T:DOwned>(client:Client,hash:HashMap<String,String>) -> Result<T,E> {
+pub fn vulns<T:DOwned>(client:Client,query:VulnQuery) -> Result<T,E> {
     let mut body=Vec::new();
     let mut serializer=Serializer::new(&mut body);
-    let byte=req::db::nc("db.vulns".to_string(),client.token.as_ref().unwrap().to_string(),hash);
+    let byte=req::db::list("db.vulns".to_string(),client.gettoken(),query);
     byte.serialize(&mut serializer).unwrap();
-    r#return(client.url,body)
+    send(&client,body)
+}
+/// [`vulns`] decoded into [`Vuln`]s.
+pub fn vulns_typed(client:Client,query:VulnQuery) -> Result<Vec<Vuln>,E> {
+    let mut reply:HashMap<String,Vec<Vuln>>=vulns(client,query)?;
+    Ok(reply.remove("vulns").unwrap_or_default())
 }
 pub fn del_vuln<T:DOwned>(client:Client,hash:HashMap<String,String>) -> Result<T,E> {
     let mut body=Vec::new();
     let mut serializer=Serializer::new(&mut body);
-    let byte=req::db::grd_vuln("db.del_vuln".to_string(),client.token.as_ref().unwrap().to_string(),hash);
+    let byte=req::db::grd_vuln("db.del_vuln".to_string(),client.gettoken(),hash);
     byte.serialize(&mut serializer).unwrap();
-    r#return(client.url,body)
+    send(&client,body)
 }
 pub fn report_vuln<T:DOwned>(client:Client,hash:HashMap<String,String>) -> Result<T,E> {
     let mut body=Vec::new();
     let mut serializer=Serializer::new(&mut body);
-    let byte=req::db::grd_vuln("db.report_vuln".to_string(),client.token.as_ref().unwrap().to_string(),hash);
+    let byte=req::db::grd_vuln("db.report_vuln".to_string(),client.gettoken(),hash);
     byte.serialize(&mut serializer).unwrap();
-    r#return(client.url,body)
+    send(&client,body)
 }
 pub fn get_vuln<T:DOwned>(client:Client,hash:HashMap<String,String>) -> Result<T,E> {
     let mut body=Vec::new();
     let mut serializer=Serializer::new(&mut body);
-    let byte=req::db::grd_vuln("db.get_vuln".to_string(),client.token.as_ref().unwrap().to_string(),hash);
+    let byte=req::db::grd_vuln("db.get_vuln".to_string(),client.gettoken(),hash);
     byte.serialize(&mut serializer).unwrap();
-    r#return(client.url,body)
+    send(&client,body)
 }
 
 pub fn workspaces<T:DOwned>(client:Client) -> Result<T,E> {
     let mut body=Vec::new();
     let mut serializer=Serializer::new(&mut body);
-    let byte=req::db::workspaces("db.workspaces".to_string(),client.token.as_ref().unwrap().to_string());
+    let byte=req::db::workspaces("db.workspaces".to_string(),client.gettoken());
     byte.serialize(&mut serializer).unwrap();
-    r#return(client.url,body)
+    send(&client,body)
 }
 pub fn current_workspace<T:DOwned>(client:Client) -> Result<T,E> {
     let mut body=Vec::new();
     let mut serializer=Serializer::new(&mut body);
-    let byte=req::db::current_workspace("db.current_workspace".to_string(),client.token.as_ref().unwrap().to_string());
+    let byte=req::db::current_workspace("db.current_workspace".to_string(),client.gettoken());
     byte.serialize(&mut serializer).unwrap();
-    r#return(client.url,body)
+    send(&client,body)
 }
 pub fn get_workspace<T:DOwned>(client:Client,workspace:&str) -> Result<T,E> {
     let mut body=Vec::new();
     let mut serializer=Serializer::new(&mut body);
-    let byte=req::db::gsda_workspace("db.get_workspace".to_string(),client.token.as_ref().unwrap().to_string(),workspace.to_string());
+    let byte=req::db::gsda_workspace("db.get_workspace".to_string(),client.gettoken(),workspace.to_string());
     byte.serialize(&mut serializer).unwrap();
-    r#return(client.url,body)
+    send(&client,body)
 }
 pub fn set_workspace<T:DOwned>(client:Client,workspace:&str) -> Result<T,E> {
     let mut body=Vec::new();
     let mut serializer=Serializer::new(&mut body);
-    let byte=req::db::gsda_workspace("db.set_workspace".to_string(),client.token.as_ref().unwrap().to_string(),workspace.to_string());
+    let byte=req::db::gsda_workspace("db.set_workspace".to_string(),client.gettoken(),workspace.to_string());
     byte.serialize(&mut serializer).unwrap();
-    r#return(client.url,body)
+    send(&client,body)
 }
 pub fn del_workspace<T:DOwned>(client:Client,workspace:&str) -> Result<T,E> {
     let mut body=Vec::new();
     let mut serializer=Serializer::new(&mut body);
-    let byte=req::db::gsda_workspace("db.del_workspace".to_string(),client.token.as_ref().unwrap().to_string(),workspace.to_string());
+    let byte=req::db::gsda_workspace("db.del_workspace".to_string(),client.gettoken(),workspace.to_string());
     byte.serialize(&mut serializer).unwrap();
-    r#return(client.url,body)
+    send(&client,body)
 }
 pub fn add_workspace<T:DOwned>(client:Client,workspace:&str) -> Result<T,E> {
     let mut body=Vec::new();
     let mut serializer=Serializer::new(&mut body);
-    let byte=req::db::gsda_workspace("db.add_workspace".to_string(),client.token.as_ref().unwrap().to_string(),workspace.to_string());
+    let byte=req::db::gsda_workspace("db.add_workspace".to_string(),client.gettoken(),workspace.to_string());
     byte.serialize(&mut serializer).unwrap();
-    r#return(client.url,body)
+    send(&client,body)
 }
 
 pub fn get_note<T:DOwned>(client:Client,hash:HashMap<String,String>) -> Result<T,E> {
     let mut body=Vec::new();
     let mut serializer=Serializer::new(&mut body);
-    let byte=req::db::grd_note("db.get_note".to_string(),client.token.as_ref().unwrap().to_string(),hash);
+    let byte=req::db::grd_note("db.get_note".to_string(),client.gettoken(),hash);
     byte.serialize(&mut serializer).unwrap();
-    r#return(client.url,body)
+    send(&client,body)
 }
 pub fn report_note<T:DOwned>(client:Client,hash:HashMap<String,String>) -> Result<T,E> {
     let mut body=Vec::new();
     let mut serializer=Serializer::new(&mut body);
-    let byte=req::db::grd_note("db.report_note".to_string(),client.token.as_ref().unwrap().to_string(),hash);
+    let byte=req::db::grd_note("db.report_note".to_string(),client.gettoken(),hash);
     byte.serialize(&mut serializer).unwrap();
-    r#return(client.url,body)
+    send(&client,body)
 }
-pub fn notes<T:DOwned>(client:Client,hash:HashMap<String,String>) -> Result<T,E> {
+pub fn notes<T:DOwned>(client:Client,query:NoteQuery) -> Result<T,E> {
     let mut body=Vec::new();
     let mut serializer=Serializer::new(&mut body);
-    let byte=req::db::nc("db.notes".to_string(),client.token.as_ref().unwrap().to_string(),hash);
+    let byte=req::db::list("db.notes".to_string(),client.gettoken(),query);
     byte.serialize(&mut serializer).unwrap();
-    r#return(client.url,body)
+    send(&client,body)
+}
+/// [`notes`] decoded into [`Note`]s.
+pub fn notes_typed(client:Client,query:NoteQuery) -> Result<Vec<Note>,E> {
+    let mut reply:HashMap<String,Vec<Note>>=notes(client,query)?;
+    Ok(reply.remove("notes").unwrap_or_default())
 }
 pub fn del_note<T:DOwned>(client:Client,hash:HashMap<String,String>) -> Result<T,E> {
     let mut body=Vec::new();
     let mut serializer=Serializer::new(&mut body);
-    let byte=req::db::grd_note("db.del_note".to_string(),client.token.as_ref().unwrap().to_string(),hash);
+    let byte=req::db::grd_note("db.del_note".to_string(),client.gettoken(),hash);
     byte.serialize(&mut serializer).unwrap();
-    r#return(client.url,body)
+    send(&client,body)
 }
 
 pub fn get_client<T:DOwned>(client:Client,hash:HashMap<String,String>) -> Result<T,E> {
     let mut body=Vec::new();
     let mut serializer=Serializer::new(&mut body);
-    let byte=req::db::grd_client("db.get_client".to_string(),client.token.as_ref().unwrap().to_string(),hash);
+    let byte=req::db::grd_client("db.get_client".to_string(),client.gettoken(),hash);
     byte.serialize(&mut serializer).unwrap();
-    r#return(client.url,body)
+    send(&client,body)
 }
-pub fn clients<T:DOwned>(client:Client,hash:HashMap<String,String>) -> Result<T,E> {
+pub fn clients<T:DOwned>(client:Client,query:ClientQuery) -> Result<T,E> {
     let mut body=Vec::new();
     let mut serializer=Serializer::new(&mut body);
-    let byte=req::db::nc("db.clients".to_string(),client.token.as_ref().unwrap().to_string(),hash);
+    let byte=req::db::list("db.clients".to_string(),client.gettoken(),query);
     byte.serialize(&mut serializer).unwrap();
-    r#return(client.url,body)
+    send(&client,body)
+}
+/// [`clients`] decoded into [`WebClient`]s.
+pub fn clients_typed(client:Client,query:ClientQuery) -> Result<Vec<WebClient>,E> {
+    let mut reply:HashMap<String,Vec<WebClient>>=clients(client,query)?;
+    Ok(reply.remove("clients").unwrap_or_default())
 }
 pub fn del_client<T:DOwned>(client:Client,hash:HashMap<String,String>) -> Result<T,E> {
     let mut body=Vec::new();
     let mut serializer=Serializer::new(&mut body);
-    let byte=req::db::grd_client("db.del_client".to_string(),client.token.as_ref().unwrap().to_string(),hash);
+    let byte=req::db::grd_client("db.del_client".to_string(),client.gettoken(),hash);
     byte.serialize(&mut serializer).unwrap();
-    r#return(client.url,body)
+    send(&client,body)
 }
 pub fn report_client<T:DOwned>(client:Client,hash:HashMap<String,String>) -> Result<T,E> {
     let mut body=Vec::new();
     let mut serializer=Serializer::new(&mut body);
-    let byte=req::db::grd_client("db.report_client".to_string(),client.token.as_ref().unwrap().to_string(),hash);
+    let byte=req::db::grd_client("db.report_client".to_string(),client.gettoken(),hash);
     byte.serialize(&mut serializer).unwrap();
-    r#return(client.url,body)
+    send(&client,body)
 }
 
 pub fn get_ref<T:DOwned>(client:Client,ref_name:&str) -> Result<T,E> {
     let mut body=Vec::new();
     let mut serializer=Serializer::new(&mut body);
-    let byte=req::db::get_ref("db.get_ref".to_string(),client.token.as_ref().unwrap().to_string(),ref_name.to_string());
+    let byte=req::db::get_ref("db.get_ref".to_string(),client.gettoken(),ref_name.to_string());
     byte.serialize(&mut serializer).unwrap();
-    r#return(client.url,body)
+    send(&client,body)
 }
 
-pub fn events<T:DOwned>(client:Client,hash:HashMap<String,String>) -> Result<T,E> {
+pub fn events<T:DOwned>(client:Client,query:ListQuery) -> Result<T,E> {
     let mut body=Vec::new();
     let mut serializer=Serializer::new(&mut body);
-    let byte=req::db::nc("db.events".to_string(),client.token.as_ref().unwrap().to_string(),hash);
+    let byte=req::db::list("db.events".to_string(),client.gettoken(),query);
     byte.serialize(&mut serializer).unwrap();
-    r#return(client.url,body)
+    send(&client,body)
+}
+/// [`events`] decoded into [`Event`]s.
+pub fn events_typed(client:Client,query:ListQuery) -> Result<Vec<Event>,E> {
+    let mut reply:HashMap<String,Vec<Event>>=events(client,query)?;
+    Ok(reply.remove("events").unwrap_or_default())
 }
 pub fn report_event<T:DOwned>(client:Client,hash:HashMap<String,String>) -> Result<T,E> {
     let mut body=Vec::new();
     let mut serializer=Serializer::new(&mut body);
-    let byte=req::db::report_event("db.report_event".to_string(),client.token.as_ref().unwrap().to_string(),hash);
+    let byte=req::db::report_event("db.report_event".to_string(),client.gettoken(),hash);
     byte.serialize(&mut serializer).unwrap();
-    r#return(client.url,body)
+    send(&client,body)
 }
 
 pub fn report_loot<T:DOwned>(client:Client,hash:HashMap<String,String>) -> Result<T,E> {
     let mut body=Vec::new();
     let mut serializer=Serializer::new(&mut body);
-    let byte=req::db::report_loot("db.report_loot".to_string(),client.token.as_ref().unwrap().to_string(),hash);
+    let byte=req::db::report_loot("db.report_loot".to_string(),client.gettoken(),hash);
     byte.serialize(&mut serializer).unwrap();
-    r#return(client.url,body)
+    send(&client,body)
 }
-pub fn loots<T:DOwned>(client:Client,hash:HashMap<String,String>) -> Result<T,E> {
+pub fn loots<T:DOwned>(client:Client,query:ListQuery) -> Result<T,E> {
     let mut body=Vec::new();
     let mut serializer=Serializer::new(&mut body);
-    let byte=req::db::nc("db.loots".to_string(),client.token.as_ref().unwrap().to_string(),hash);
+    let byte=req::db::list("db.loots".to_string(),client.gettoken(),query);
     byte.serialize(&mut serializer).unwrap();
-    r#return(client.url,body)
+    send(&client,body)
+}
+/// [`loots`] decoded into [`Loot`]s.
+pub fn loots_typed(client:Client,query:ListQuery) -> Result<Vec<Loot>,E> {
+    let mut reply:HashMap<String,Vec<Loot>>=loots(client,query)?;
+    Ok(reply.remove("loots").unwrap_or_default())
 }
 
-pub fn creds<T:DOwned>(client:Client,hash:HashMap<String,String>) -> Result<T,E> {
+pub fn creds<T:DOwned>(client:Client,query:ListQuery) -> Result<T,E> {
     let mut body=Vec::new();
     let mut serializer=Serializer::new(&mut body);
-    let byte=req::db::nc("db.creds".to_string(),client.token.as_ref().unwrap().to_string(),hash);
+    let byte=req::db::list("db.creds".to_string(),client.gettoken(),query);
     byte.serialize(&mut serializer).unwrap();
-    r#return(client.url,body)
+    send(&client,body)
+}
+/// [`creds`] decoded into [`Cred`]s.
+pub fn creds_typed(client:Client,query:ListQuery) -> Result<Vec<Cred>,E> {
+    let mut reply:HashMap<String,Vec<Cred>>=creds(client,query)?;
+    Ok(reply.remove("creds").unwrap_or_default())
 }
 
 pub fn import_data<T:DOwned>(client:Client,data:&str) -> Result<T,E> {
     let mut body=Vec::new();
     let mut serializer=Serializer::new(&mut body);
-    let byte=req::db::import_db("db.import_data".to_string(),client.token.as_ref().unwrap().to_string(),data.to_string());
+    let byte=req::db::import_db("db.import_data".to_string(),client.gettoken(),data.to_string());
     byte.serialize(&mut serializer).unwrap();
-    r#return(client.url,body)
+    send(&client,body)
 }
 pub fn import_file<T:DOwned>(client:Client,mut file:File) -> Result<T,E> {
     let mut body=Vec::new();
     let mut serializer=Serializer::new(&mut body);
     let mut data=String::new();
     file.read_to_string(&mut data).unwrap();
-    let byte=req::db::import_db("db.import_data".to_string(),client.token.as_ref().unwrap().to_string(),data);
+    let byte=req::db::import_db("db.import_data".to_string(),client.gettoken(),data);
     byte.serialize(&mut serializer).unwrap();
-    r#return(client.url,body)
+    send(&client,body)
 }
 pub fn set_driver<T:DOwned>(client:Client,driver:&str) -> Result<T,E> {
     let mut body=Vec::new();
     let mut hash:HashMap<String,String>=HashMap::new();
     let mut serializer=Serializer::new(&mut body);
     hash.insert("driver".to_string(),driver.to_string());
-    let byte=req::db::driver("db.driver".to_string(),client.token.as_ref().unwrap().to_string(),hash);
+    let byte=req::db::driver("db.driver".to_string(),client.gettoken(),hash);
     byte.serialize(&mut serializer).unwrap();
-    r#return(client.url,body)
+    send(&client,body)
 }
 pub fn get_driver<T:DOwned>(client:Client) -> Result<T,E> {
     let mut body=Vec::new();
     let hash:HashMap<String,String>=HashMap::new();
     let mut serializer=Serializer::new(&mut body);
-    let byte=req::db::driver("db.driver".to_string(),client.token.as_ref().unwrap().to_string(),hash);
+    let byte=req::db::driver("db.driver".to_string(),client.gettoken(),hash);
     byte.serialize(&mut serializer).unwrap();
-    r#return(client.url,body)
+    send(&client,body)
 }
 
 pub fn dbconnect<T:DOwned>(client:Client,driver:&str,mut hash:HashMap<String,String>) -> Result<T,E> {
     let mut body=Vec::new();
     hash.insert("driver".to_string(),driver.to_string());
     let mut serializer=Serializer::new(&mut body);
-    let byte=req::db::connect("db.connect".to_string(),client.token.as_ref().unwrap().to_string(),hash);
+    let byte=req::db::connect("db.connect".to_string(),client.gettoken(),hash);
     byte.serialize(&mut serializer).unwrap();
-    r#return(client.url,body)
+    send(&client,body)
 }
 
 pub fn status<T:DOwned>(client:Client) -> Result<T,E> {
     let mut body=Vec::new();
     let mut serializer=Serializer::new(&mut body);
-    let byte=req::db::status("db.status".to_string(),client.token.as_ref().unwrap().to_string());
+    let byte=req::db::status("db.status".to_string(),client.gettoken());
     byte.serialize(&mut serializer).unwrap();
-    r#return(client.url,body)
+    send(&client,body)
 }
 
 pub fn disconnect<T:DOwned>(client:Client) -> Result<T,E> {
     let mut body=Vec::new();
     let mut serializer=Serializer::new(&mut body);
-    let byte=req::db::disconnect("db.disconnect".to_string(),client.token.as_ref().unwrap().to_string());
+    let byte=req::db::disconnect("db.disconnect".to_string(),client.gettoken());
     byte.serialize(&mut serializer).unwrap();
-    r#return(client.url,body)
+    send(&client,body)
+}
+
+/// Records of a database listing,fetched `page_size` at a time with `limit` and `offset` as they are consumed.
+///
+/// Returned by [`hosts_paged`] and the other `*_paged` functions.Only one page is held in memory,and dropping the
+/// iterator stops the listing.A failed call is yielded once and ends the iteration.
+pub struct Pages<T> {
+    fetch:Box<dyn FnMut(u32,u32) -> Result<Vec<T>,E>+Send>,
+    page_size:u32,
+    offset:u32,
+    page:std::vec::IntoIter<T>,
+    done:bool,
+}
+
+impl<T> Pages<T> {
+    fn new(page_size:u32,fetch:Box<dyn FnMut(u32,u32) -> Result<Vec<T>,E>+Send>) -> Self {
+        Pages {
+            fetch,
+            page_size:page_size.max(1),
+            offset:0,
+            page:Vec::new().into_iter(),
+            done:false,
+        }
+    }
 }
 
-fn r#return<T:DOwned>(url:String,body:Vec<u8>) -> Result<T,E> {
-    let mut buf=vec![];
-    let con=connect::connect(url,body,&mut buf);
-    let new_buf=buf.clone();
-    match con {
-        Ok(_) => {
-            let ret:Result<T,derror>=from_read(new_buf.as_slice());
-            match ret {
-                Ok(val) => {
-                    Ok(val)
+impl<T> Iterator for Pages<T> {
+    type Item=Result<T,E>;
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(record)=self.page.next() {
+                return Some(Ok(record));
+            }
+            if self.done {
+                return None;
+            }
+            match (self.fetch)(self.offset,self.page_size) {
+                Ok(page) => {
+                    self.done=page.len()<self.page_size as usize;
+                    self.offset+=page.len() as u32;
+                    self.page=page.into_iter();
                 },
-                Err(_) => {
-                    let ret2:Result<MsfError,derror>=from_read(new_buf.as_slice());
-                    match ret2 {
-                        Ok(e) => {
-                            Err(E::MsfError(e))
-                        },
-                        Err(e) => {
-                            Err(E::DError(e))
-                        },
-                    }
+                Err(e) => {
+                    self.done=true;
+                    return Some(Err(e));
                 },
             }
-        },
-        Err(e) => {
-            Err(E::ConnectionError(e))
-        },
+        }
+    }
+}
+
+/// [`hosts_typed`] page by page.The query's `limit` and `offset` are replaced by the paging.
+pub fn hosts_paged(client:Client,query:HostQuery,page_size:u32) -> Pages<Host> {
+    Pages::new(page_size,Box::new(move |offset,limit| hosts_typed(client.clone(),query.clone().offset(offset).limit(limit))))
+}
+/// [`services_typed`] page by page.The query's `limit` and `offset` are replaced by the paging.
+pub fn services_paged(client:Client,query:ServiceQuery,page_size:u32) -> Pages<Service> {
+    Pages::new(page_size,Box::new(move |offset,limit| services_typed(client.clone(),query.clone().offset(offset).limit(limit))))
+}
+/// [`vulns_typed`] page by page.The query's `limit` and `offset` are replaced by the paging.
+pub fn vulns_paged(client:Client,query:VulnQuery,page_size:u32) -> Pages<Vuln> {
+    Pages::new(page_size,Box::new(move |offset,limit| vulns_typed(client.clone(),query.clone().offset(offset).limit(limit))))
+}
+/// [`notes_typed`] page by page.The query's `limit` and `offset` are replaced by the paging.
+pub fn notes_paged(client:Client,query:NoteQuery,page_size:u32) -> Pages<Note> {
+    Pages::new(page_size,Box::new(move |offset,limit| notes_typed(client.clone(),query.clone().offset(offset).limit(limit))))
+}
+/// [`clients_typed`] page by page.The query's `limit` and `offset` are replaced by the paging.
+pub fn clients_paged(client:Client,query:ClientQuery,page_size:u32) -> Pages<WebClient> {
+    Pages::new(page_size,Box::new(move |offset,limit| clients_typed(client.clone(),query.clone().offset(offset).limit(limit))))
+}
+/// [`events_typed`] page by page.The query's `limit` and `offset` are replaced by the paging.
+pub fn events_paged(client:Client,query:ListQuery,page_size:u32) -> Pages<Event> {
+    Pages::new(page_size,Box::new(move |offset,limit| events_typed(client.clone(),query.clone().offset(offset).limit(limit))))
+}
+/// [`loots_typed`] page by page.The query's `limit` and `offset` are replaced by the paging.
+pub fn loots_paged(client:Client,query:ListQuery,page_size:u32) -> Pages<Loot> {
+    Pages::new(page_size,Box::new(move |offset,limit| loots_typed(client.clone(),query.clone().offset(offset).limit(limit))))
+}
+/// [`creds_typed`] page by page.The query's `limit` and `offset` are replaced by the paging.
+pub fn creds_paged(client:Client,query:ListQuery,page_size:u32) -> Pages<Cred> {
+    Pages::new(page_size,Box::new(move |offset,limit| creds_typed(client.clone(),query.clone().offset(offset).limit(limit))))
+}
+
+/// A database workspace,opened with [`Client::workspace`].
+///
+/// Every call made through the handle names the workspace,so it never depends on or changes the server's current
+/// workspace,which is shared by everyone using the same msfrpcd.
+#[derive(Debug,Clone)]
+pub struct Workspace {
+    client:Client,
+    name:String,
+}
+
+impl Workspace {
+    /// Creates the workspace unless it exists.
+    pub(crate) fn open(client:Client,name:&str) -> Result<Self,E> {
+        let _:Value=add_workspace(client.clone(),name)?;
+        Ok(Workspace {
+            client,
+            name:name.to_string(),
+        })
+    }
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    fn scoped(&self,mut hash:HashMap<String,String>) -> HashMap<String,String> {
+        hash.insert("workspace".to_string(),self.name.clone());
+        hash
+    }
+    pub fn hosts_typed(&self,query:HostQuery) -> Result<Vec<Host>,E> {
+        hosts_typed(self.client.clone(),query.workspace(&self.name))
+    }
+    pub fn hosts_paged(&self,query:HostQuery,page_size:u32) -> Pages<Host> {
+        hosts_paged(self.client.clone(),query.workspace(&self.name),page_size)
+    }
+    pub fn get_host<T:DOwned>(&self,host:&str) -> Result<T,E> {
+        get_host(self.client.clone(),Some(self.name.clone()),host)
+    }
+    pub fn report_host<T:DOwned>(&self,host:&str) -> Result<T,E> {
+        report_host(self.client.clone(),Some(self.name.clone()),host)
+    }
+    pub fn del_host<T:DOwned>(&self,host:&str) -> Result<T,E> {
+        del_host(self.client.clone(),Some(self.name.clone()),host)
+    }
+    pub fn services_typed(&self,query:ServiceQuery) -> Result<Vec<Service>,E> {
+        services_typed(self.client.clone(),query.workspace(&self.name))
+    }
+    pub fn services_paged(&self,query:ServiceQuery,page_size:u32) -> Pages<Service> {
+        services_paged(self.client.clone(),query.workspace(&self.name),page_size)
+    }
+    pub fn get_service<T:DOwned>(&self,hash:HashMap<String,String>) -> Result<T,E> {
+        get_service(self.client.clone(),self.scoped(hash))
+    }
+    pub fn report_service<T:DOwned>(&self,hash:HashMap<String,String>) -> Result<T,E> {
+        report_service(self.client.clone(),self.scoped(hash))
+    }
+    pub fn del_service<T:DOwned>(&self,hash:HashMap<String,String>) -> Result<T,E> {
+        del_service(self.client.clone(),self.scoped(hash))
+    }
+    pub fn vulns_typed(&self,query:VulnQuery) -> Result<Vec<Vuln>,E> {
+        vulns_typed(self.client.clone(),query.workspace(&self.name))
+    }
+    pub fn vulns_paged(&self,query:VulnQuery,page_size:u32) -> Pages<Vuln> {
+        vulns_paged(self.client.clone(),query.workspace(&self.name),page_size)
+    }
+    pub fn get_vuln<T:DOwned>(&self,hash:HashMap<String,String>) -> Result<T,E> {
+        get_vuln(self.client.clone(),self.scoped(hash))
+    }
+    pub fn report_vuln<T:DOwned>(&self,hash:HashMap<String,String>) -> Result<T,E> {
+        report_vuln(self.client.clone(),self.scoped(hash))
+    }
+    pub fn del_vuln<T:DOwned>(&self,hash:HashMap<String,String>) -> Result<T,E> {
+        del_vuln(self.client.clone(),self.scoped(hash))
+    }
+    pub fn notes_typed(&self,query:NoteQuery) -> Result<Vec<Note>,E> {
+        notes_typed(self.client.clone(),query.workspace(&self.name))
+    }
+    pub fn notes_paged(&self,query:NoteQuery,page_size:u32) -> Pages<Note> {
+        notes_paged(self.client.clone(),query.workspace(&self.name),page_size)
+    }
+    pub fn get_note<T:DOwned>(&self,hash:HashMap<String,String>) -> Result<T,E> {
+        get_note(self.client.clone(),self.scoped(hash))
+    }
+    pub fn report_note<T:DOwned>(&self,hash:HashMap<String,String>) -> Result<T,E> {
+        report_note(self.client.clone(),self.scoped(hash))
+    }
+    pub fn del_note<T:DOwned>(&self,hash:HashMap<String,String>) -> Result<T,E> {
+        del_note(self.client.clone(),self.scoped(hash))
+    }
+    pub fn loots_typed(&self,query:ListQuery) -> Result<Vec<Loot>,E> {
+        loots_typed(self.client.clone(),query.workspace(&self.name))
+    }
+    pub fn loots_paged(&self,query:ListQuery,page_size:u32) -> Pages<Loot> {
+        loots_paged(self.client.clone(),query.workspace(&self.name),page_size)
+    }
+    pub fn report_loot<T:DOwned>(&self,hash:HashMap<String,String>) -> Result<T,E> {
+        report_loot(self.client.clone(),self.scoped(hash))
+    }
+    pub fn creds_typed(&self,query:ListQuery) -> Result<Vec<Cred>,E> {
+        creds_typed(self.client.clone(),query.workspace(&self.name))
+    }
+    pub fn creds_paged(&self,query:ListQuery,page_size:u32) -> Pages<Cred> {
+        creds_paged(self.client.clone(),query.workspace(&self.name),page_size)
     }
 }
@@ -1,110 +1,30 @@
-#[path="../../structs/mod.rs"] mod structs;
-#[path="../../connect.rs"] mod connect;
 use crate::client::Client;
-use connect::connect;
-use crate::error::{MsfError,Error as E};
-use rmp_serde::{Serializer,decode::Error as derror,from_read};
+use crate::connect::send;
+use crate::error::Error as E;
+use rmp_serde::Serializer;
 use serde::{Serialize,de::DeserializeOwned as DOwned};
-use structs::request as req;
+use crate::structs::request as req;
 
 pub fn list<T:DOwned>(client:Client) -> Result<T,E> {
     let mut body=Vec::new();
-    let mut buf=vec![];
     let mut se=Serializer::new(&mut body);
-    let byte=req::jobs::list("job.list".to_string(),client.token.unwrap());
+    let byte=req::jobs::list("job.list".to_string(),client.gettoken());
     byte.serialize(&mut se).unwrap();
-    let con=connect(client.url,body,&mut buf);
-    let new_buf=buf.clone();
-    match con {
-        Ok(_) => {
-            let ret:Result<T,derror>=from_read(new_buf.as_slice());
-            match ret {
-                Ok(val) => {
-                    Ok(val)
-                },
-                Err(_) => {
-                    let ret2:Result<MsfError,derror>=from_read(new_buf.as_slice());
-                    match ret2 {
-                        Ok(val) => {
-                            Err(E::MsfError(val))
-                        },
-                        Err(e) => {
-                            Err(E::DError(e))
-                        },
-                    }
-                }
-            }
-        },
-        Err(e) => {
-            Err(E::ConnectionError(e))
-        },
-    }
+    send(&client,body)
 }
 pub fn info<T:DOwned>(client:Client,jobidstr:&str) -> Result<T,E> {
     let jobid:String=jobidstr.to_string();
     let mut body=Vec::new();
-    let mut buf=vec![];
     let mut se=Serializer::new(&mut body);
-    let byte=req::jobs::info("job.info".to_string(),client.token.unwrap(),jobid);
+    let byte=req::jobs::info("job.info".to_string(),client.gettoken(),jobid);
     byte.serialize(&mut se).unwrap();
-    let con=connect(client.url,body,&mut buf);
-    let new_buf=buf.clone();
-    match con {
-        Ok(_) => {
-            let ret:Result<T,derror>=from_read(new_buf.as_slice());
-            match ret {
-                Ok(val) => {
-                    Ok(val)
-                },
-                Err(_) => {
-                    let ret2:Result<MsfError,derror>=from_read(new_buf.as_slice());
-                    match ret2 {
-                        Ok(val) => {
-                            Err(E::MsfError(val))
-                        },
-                        Err(e) => {
-                            Err(E::DError(e))
-                        },
-                    }
-                }
-            }
-        },
-        Err(e) => {
-            Err(E::ConnectionError(e))
-        },
-    }
+    send(&client,body)
 }
 pub fn stop<T:DOwned>(client:Client,jobidstr:&str) -> Result<T,E> {
     let jobid:String=jobidstr.to_string();
     let mut body=Vec::new();
-    let mut buf=vec![];
     let mut se=Serializer::new(&mut body);
-    let byte=req::jobs::stop("job.stop".to_string(),client.token.unwrap(),jobid);
+    let byte=req::jobs::stop("job.stop".to_string(),client.gettoken(),jobid);
     byte.serialize(&mut se).unwrap();
-    let con=connect(client.url,body,&mut buf);
-    let new_buf=buf.clone();
-    match con {
-        Ok(_) => {
-            let ret:Result<T,derror>=from_read(new_buf.as_slice());
-            match ret {
-                Ok(val) => {
-                    Ok(val)
-                },
-                Err(_) => {
-                    let ret2:Result<MsfError,derror>=from_read(new_buf.as_slice());
-                    match ret2 {
-                        Ok(val) => {
-                            Err(E::MsfError(val))
-                        },
-                        Err(e) => {
-                            Err(E::DError(e))
-                        },
-                    }
-                }
-            }
-        },
-        Err(e) => {
-            Err(E::ConnectionError(e))
-        },
-    }
+    send(&client,body)
 }
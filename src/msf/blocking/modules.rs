@@ -1,14 +1,14 @@
 #![allow(non_camel_case_types)]
 #![allow(unused_assignments)]
-#[path="../../structs/mod.rs"] mod structs;
-#[path="../../connect.rs"] mod connect;
 use crate::client::Client;
-use connect::connect;
+use crate::connect::send;
 use std::collections::HashMap;
 use serde::{Serialize,de::DeserializeOwned as DOwned};
-use rmp_serde::{Serializer,decode::Error as derror,from_read};
-use crate::error::{MsfError,Error as E};
-use structs::request as req;
+use rmp_serde::Serializer;
+use crate::error::Error as E;
+use crate::structs::request as req;
+use crate::structs::response::modules::{ModuleInfo,ModuleOption};
+use std::collections::BTreeMap;
 
 pub struct compactible {
     pub name:String,
@@ -20,368 +20,131 @@ pub struct list {
 impl list {
     pub fn new(client:Client) -> Self {
         list {
-            client:client,
+            client,
         }
     }
     fn serialize(&self,method:&str,body:&mut Vec<u8>) {
         let mut se=Serializer::new(body);
-        let byte=req::modules::list(method.to_string(),self.client.token.as_ref().unwrap().to_string());
+        let byte=req::modules::list(method.to_string(),self.client.gettoken());
         byte.serialize(&mut se).unwrap();
     }
-    fn deserialize<T:DOwned>(&self,new_buf:Vec<u8>) -> Result<T,E> {
-        let ret:Result<T,derror>=from_read(new_buf.as_slice());
-        match ret {
-            Ok(val) => {
-                Ok(val)
-            },
-            Err(_) => {
-                let ret2:Result<MsfError,derror>=from_read(new_buf.as_slice());
-                match ret2 {
-                    Ok(val) => {
-                        Err(E::MsfError(val))
-                    },
-                    Err(e) => {
-                        Err(E::DError(e))
-                    },
-                }
-            },
-        }
-    }
     pub fn exploits<T:DOwned>(&self) -> Result<T,E> {
         let mut body=Vec::new();
-        let mut buf=vec![];
         self.serialize("module.exploits",&mut body);
-        let con=connect(self.client.url.clone(),body,&mut buf);
-        let new_buf=buf.clone();
-        match con {
-            Ok(_) => {
-                self.deserialize(new_buf)
-            },
-            Err(e) => {
-                Err(E::ConnectionError(e))
-            },
-        }
+        send(&self.client,body)
     }
     pub fn auxiliary<T:DOwned>(&self) -> Result<T,E> {
         let mut body=Vec::new();
-        let mut buf=vec![];
         self.serialize("module.auxiliary",&mut body);
-        let con=connect(self.client.url.clone(),body,&mut buf);
-        let new_buf=buf.clone();
-        match con {
-            Ok(_) => {
-                self.deserialize(new_buf)
-            },
-            Err(e) => {
-                Err(E::ConnectionError(e))
-            },
-        }
+        send(&self.client,body)
     }
     pub fn post<T:DOwned>(&self) -> Result<T,E> {
         let mut body=Vec::new();
-        let mut buf=vec![];
         self.serialize("module.post",&mut body);
-        let con=connect(self.client.url.clone(),body,&mut buf);
-        let new_buf=buf.clone();
-        match con {
-            Ok(_) => {
-                self.deserialize(new_buf)
-            },
-            Err(e) => {
-                Err(E::ConnectionError(e))
-            },
-        }
+        send(&self.client,body)
     }
     pub fn payloads<T:DOwned>(&self) -> Result<T,E> {
         let mut body=Vec::new();
-        let mut buf=vec![];
         self.serialize("module.payloads",&mut body);
-        let con=connect(self.client.url.clone(),body,&mut buf);
-        let new_buf=buf.clone();
-        match con {
-            Ok(_) => {
-                self.deserialize(new_buf)
-            },
-            Err(e) => {
-                Err(E::ConnectionError(e))
-            },
-        }
+        send(&self.client,body)
     }
     pub fn encoders<T:DOwned>(&self) -> Result<T,E> {
         let mut body=Vec::new();
-        let mut buf=vec![];
         self.serialize("module.encoders",&mut body);
-        let con=connect(self.client.url.clone(),body,&mut buf);
-        let new_buf=buf.clone();
-        match con {
-            Ok(_) => {
-                self.deserialize(new_buf)
-            },
-            Err(e) => {
-                Err(E::ConnectionError(e))
-            },
-        }
+        send(&self.client,body)
     }
     pub fn nops<T:DOwned>(&self) -> Result<T,E> {
         let mut body=Vec::new();
-        let mut buf=vec![];
         self.serialize("module.nops",&mut body);
-        let con=connect(self.client.url.clone(),body,&mut buf);
-        let new_buf=buf.clone();
-        match con {
-            Ok(_) => {
-                self.deserialize(new_buf)
-            },
-            Err(e) => {
-                Err(E::ConnectionError(e))
-            },
-        }
+        send(&self.client,body)
     }
 }
 pub fn info<T:DOwned>(client:Client,moduletypestr:&str,modulenamestr:&str) -> Result<T,E> {
     let moduletype:String=moduletypestr.to_string();
     let modulename:String=modulenamestr.to_string();
     let mut body=Vec::new();
-    let mut buf=vec![];
     let mut se=Serializer::new(&mut body);
-    let byte=req::modules::info("module.info".to_string(),client.token.unwrap(),moduletype,modulename);
+    let byte=req::modules::info("module.info".to_string(),client.gettoken(),moduletype,modulename);
     byte.serialize(&mut se).unwrap();
-    let con=connect(client.url,body,&mut buf);
-    let new_buf=buf.clone();
-    match con {
-        Ok(_) => {
-            let ret:Result<T,derror>=from_read(new_buf.as_slice());
-            match ret {
-                Ok(val) => {
-                    Ok(val)
-                },
-                Err(_) => {
-                    let ret2:Result<MsfError,derror>=from_read(new_buf.as_slice());
-                    match ret2 {
-                        Ok(val) => {
-                            Err(E::MsfError(val))
-                        },
-                        Err(e) => {
-                            Err(E::DError(e))
-                        },
-                    }
-                }
-            }
-        },
-        Err(e) => {
-            Err(E::ConnectionError(e))
-        },
-    }
+    send(&client,body)
+}
+/// [`info`] decoded into [`ModuleInfo`].
+pub fn info_typed(client:Client,moduletypestr:&str,modulenamestr:&str) -> Result<ModuleInfo,E> {
+    info(client,moduletypestr,modulenamestr)
 }
 impl compactible {
     pub fn new(modulename:String,client:Client) -> Self {
         compactible {
             name:modulename,
-            client:client,
+            client,
         }
     }
     pub fn payload<T:DOwned>(&self) -> Result<T,E> {
         let mut body=Vec::new();
-        let mut buf=vec![];
         let mut se=Serializer::new(&mut body);
-        let byte=req::modules::compactible("module.compatible_payloads".to_string(),self.client.token.as_ref().unwrap().to_string(),self.name.clone());
+        let byte=req::modules::compactible("module.compatible_payloads".to_string(),self.client.gettoken(),self.name.clone());
         byte.serialize(&mut se).unwrap();
-        let con=connect(self.client.url.clone(),body,&mut buf);
-        let new_buf=buf.clone();
-        match con {
-            Ok(_) => {
-                let ret:Result<T,derror>=from_read(new_buf.as_slice());
-                match ret {
-                    Ok(val) => {
-                        Ok(val)
-                    },
-                    Err(_) => {
-                        let ret2:Result<MsfError,derror>=from_read(new_buf.as_slice());
-                        match ret2 {
-                            Ok(val) => {
-                                Err(E::MsfError(val))
-                            },
-                            Err(e) => {
-                                Err(E::DError(e))
-                            },
-                        }
-                    }
-                }
-            },
-            Err(e) => {
-                Err(E::ConnectionError(e))
-            },
-        }
+        send(&self.client,body)
     }
     pub fn target_payloads<T:DOwned>(&self,targetindx:i32) -> Result<T,E> {
         let mut body=Vec::new();
-        let mut buf=vec![];
         let mut se=Serializer::new(&mut body);
-        let byte=req::modules::compactible_tp("module.target_compatible_payloads".to_string(),self.client.token.as_ref().unwrap().to_string(),self.name.clone(),targetindx);
+        let byte=req::modules::compactible_tp("module.target_compatible_payloads".to_string(),self.client.gettoken(),self.name.clone(),targetindx);
         byte.serialize(&mut se).unwrap();
-        let con=connect(self.client.url.clone(),body,&mut buf);
-        let new_buf=buf.clone();
-        match con {
-            Ok(_) => {
-                let ret:Result<T,derror>=from_read(new_buf.as_slice());
-                match ret {
-                    Ok(val) => {
-                        Ok(val)
-                    },
-                    Err(_) => {
-                        let ret2:Result<MsfError,derror>=from_read(new_buf.as_slice());
-                        match ret2 {
-                            Ok(val) => {
-                                Err(E::MsfError(val))
-                            },
-                            Err(e) => {
-                                Err(E::DError(e))
-                            },
-                        }
-                    }
-                }
-            },
-            Err(e) => {
-                Err(E::ConnectionError(e))
-            },
-        }
+        send(&self.client,body)
     }
     pub fn sessions<T:DOwned>(&self) -> Result<T,E> {
         let mut body=Vec::new();
-        let mut buf=vec![];
         let mut se=Serializer::new(&mut body);
-        let byte=req::modules::compactible("module.compatible_sessions".to_string(),self.client.token.as_ref().unwrap().to_string(),self.name.clone());
+        let byte=req::modules::compactible("module.compatible_sessions".to_string(),self.client.gettoken(),self.name.clone());
         byte.serialize(&mut se).unwrap();
-        let con=connect(self.client.url.clone(),body,&mut buf);
-        let new_buf=buf.clone();
-        match con {
-            Ok(_) => {
-                let ret:Result<T,derror>=from_read(new_buf.as_slice());
-                match ret {
-                    Ok(val) => {
-                        Ok(val)
-                    },
-                    Err(_) => {
-                        let ret2:Result<MsfError,derror>=from_read(new_buf.as_slice());
-                        match ret2 {
-                            Ok(val) => {
-                                Err(E::MsfError(val))
-                            },
-                            Err(e) => {
-                                Err(E::DError(e))
-                            },
-                        }
-                    }
-                }
-            },
-            Err(e) => {
-                Err(E::ConnectionError(e))
-            },
-        }
+        send(&self.client,body)
     }
 }
 pub fn option<T:DOwned>(client:Client,moduletypestr:&str,modulenamestr:&str) -> Result<T,E> {
     let moduletype:String=moduletypestr.to_string();
     let modulename:String=modulenamestr.to_string();
     let mut body=Vec::new();
-    let mut buf=vec![];
     let mut serializer=Serializer::new(&mut body);
-    let byte=req::modules::options("module.options".to_string(),client.token.as_ref().unwrap().to_string(),moduletype.clone(),modulename.clone());
+    let byte=req::modules::options("module.options".to_string(),client.gettoken(),moduletype.clone(),modulename.clone());
     byte.serialize(&mut serializer).unwrap();
-    let con=connect(client.url.clone(),body,&mut buf);
-    let new_buf=buf.clone();
-    match con {
-        Ok(_) => {
-            let ret:Result<T,derror>=from_read(new_buf.as_slice());
-            match ret {
-                Ok(val) => {
-                    Ok(val)
-                },
-                Err(_) => {
-                    let ret2:Result<MsfError,derror>=from_read(new_buf.as_slice());
-                    match ret2 {
-                        Ok(val) => {
-                            Err(E::MsfError(val))
-                        },
-                        Err(e) => {
-                            Err(E::DError(e))
-                        },
-                    }
-                }
-            }
-        },
-        Err(e) => {
-            Err(E::ConnectionError(e))
-        },
-    }
+    send(&client,body)
+}
+/// [`option`] decoded into [`ModuleOption`]s by option name.
+pub fn option_typed(client:Client,moduletypestr:&str,modulenamestr:&str) -> Result<BTreeMap<String,ModuleOption>,E> {
+    option(client,moduletypestr,modulenamestr)
+}
+/// The formats `msfvenom -f` can wrap a payload in,e.g. `exe` or `elf`.
+pub fn executable_formats<T:DOwned>(client:Client) -> Result<T,E> {
+    let mut body=Vec::new();
+    let mut se=Serializer::new(&mut body);
+    let byte=req::modules::list("module.executable_formats".to_string(),client.gettoken());
+    byte.serialize(&mut se).unwrap();
+    send(&client,body)
+}
+/// The formats `msfvenom -f` can render a payload as source in,e.g. `c` or `python`.
+pub fn transform_formats<T:DOwned>(client:Client) -> Result<T,E> {
+    let mut body=Vec::new();
+    let mut se=Serializer::new(&mut body);
+    let byte=req::modules::list("module.transform_formats".to_string(),client.gettoken());
+    byte.serialize(&mut se).unwrap();
+    send(&client,body)
 }
 pub fn encoder<T:DOwned>(client:Client,datastr:&str,encodermodulestr:&str,options:HashMap<String,String>) -> Result<T,E> {
     let data:String=datastr.to_string();
     let encodermodule:String=encodermodulestr.to_string();
     let mut body=Vec::new();
-    let mut buf=vec![];
     let mut se=Serializer::new(&mut body);
-    let byte=req::modules::encoder("module.encode".to_string(),client.token.unwrap(),data,encodermodule,options);
+    let byte=req::modules::encoder("module.encode".to_string(),client.gettoken(),data,encodermodule,options);
     byte.serialize(&mut se).unwrap();
-    let con=connect(client.url,body,&mut buf);
-    let new_buf=buf.clone();
-    match con {
-        Ok(_) => {
-            let ret:Result<T,derror>=from_read(new_buf.as_slice());
-            match ret {
-                Ok(val) => {
-                    Ok(val)
-                },
-                Err(_) => {
-                    let ret2:Result<MsfError,derror>=from_read(new_buf.as_slice());
-                    match ret2 {
-                        Ok(val) => {
-                            Err(E::MsfError(val))
-                        },
-                        Err(e) => {
-                            Err(E::DError(e))
-                        },
-                    }
-                }
-            }
-        },
-        Err(e) => {
-            Err(E::ConnectionError(e))
-        },
-    }
+    send(&client,body)
 }
 pub fn execute<T:DOwned>(client:Client,moduletypestr:&str,modulenamestr:&str,options:HashMap<String,String>) -> Result<T,E> {
     let moduletype:String=moduletypestr.to_string();
     let modulename:String=modulenamestr.to_string();
     let mut body=Vec::new();
-    let mut buf=vec![];
     let mut se=Serializer::new(&mut body);
-    let byte=req::modules::execute("module.execute".to_string(),client.token.unwrap(),moduletype.clone(),modulename,options);
+    let byte=req::modules::execute("module.execute".to_string(),client.gettoken(),moduletype.clone(),modulename,options);
     byte.serialize(&mut se).unwrap();
-    let con=connect(client.url,body,&mut buf);
-    let new_buf=buf.clone();
-    match con {
-        Ok(_) => {
-            let ret:Result<T,derror>=from_read(new_buf.as_slice());
-            match ret {
-                Ok(val) => {
-                    Ok(val)
-                },
-                Err(_) => {
-                    let ret2:Result<MsfError,derror>=from_read(new_buf.as_slice());
-                    match ret2 {
-                        Ok(val) => {
-                            Err(E::MsfError(val))
-                        },
-                        Err(e) => {
-                            Err(E::DError(e))
-                        },
-                    }
-                }
-            }
-        },
-        Err(e) => {
-            Err(E::ConnectionError(e))
-        },
-    }
+    send(&client,body)
 }
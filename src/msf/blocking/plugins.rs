@@ -1,112 +1,32 @@
-#[path="../../structs/mod.rs"] mod structs;
-#[path="../../connect.rs"] mod connect;
-use connect::connect;
+use crate::connect::send;
 use std::collections::HashMap;
-use crate::error::{MsfError,Error as E};
-use structs::request as req;
+use crate::error::Error as E;
+use crate::structs::request as req;
 use crate::client::Client;
 use serde::{Serialize,de::DeserializeOwned as DOwned};
-use rmp_serde::{Serializer,decode::Error as derror,from_read};
+use rmp_serde::Serializer;
 
 pub fn load<T:DOwned>(client:Client,pluginnamestr:&str,options:HashMap<String,String>) -> Result<T,E> {
     let pluginname:String=pluginnamestr.to_string();
     let mut body=Vec::new();
-    let mut buf=vec![];
     let mut se=Serializer::new(&mut body);
-    let byte=req::plugins::load("plugin.load".to_string(),client.token.unwrap(),pluginname,options);
+    let byte=req::plugins::load("plugin.load".to_string(),client.gettoken(),pluginname,options);
     byte.serialize(&mut se).unwrap();
-    let con=connect(client.url,body,&mut buf);
-    let new_buf=buf.clone();
-    match con {
-        Ok(_) => {
-            let ret:Result<T,derror>=from_read(new_buf.as_slice());
-            match ret {
-                Ok(val) => {
-                    Ok(val)
-                },
-                Err(_) => {
-                    let ret2:Result<MsfError,derror>=from_read(new_buf.as_slice());
-                    match ret2 {
-                        Ok(val) => {
-                            Err(E::MsfError(val))
-                        },
-                        Err(e) => {
-                            Err(E::DError(e))
-                        },
-                    }
-                }
-            }
-        },
-        Err(e) => {
-            Err(E::ConnectionError(e))
-        },
-    }
+    send(&client,body)
 }
 pub fn unload<T:DOwned>(client:Client,pluginnamestr:&str) -> Result<T,E> {
     let pluginname:String=pluginnamestr.to_string();
     let mut body=Vec::new();
-    let mut buf=vec![];
     let mut se=Serializer::new(&mut body);
-    let byte=req::plugins::unload("plugin.unload".to_string(),client.token.unwrap(),pluginname);
+    let byte=req::plugins::unload("plugin.unload".to_string(),client.gettoken(),pluginname);
     byte.serialize(&mut se).unwrap();
-    let con=connect(client.url,body,&mut buf);
-    let new_buf=buf.clone();
-    match con {
-        Ok(_) => {
-            let ret:Result<T,derror>=from_read(new_buf.as_slice());
-            match ret {
-                Ok(val) => {
-                    Ok(val)
-                },
-                Err(_) => {
-                    let ret2:Result<MsfError,derror>=from_read(new_buf.as_slice());
-                    match ret2 {
-                        Ok(val) => {
-                            Err(E::MsfError(val))
-                        },
-                        Err(e) => {
-                            Err(E::DError(e))
-                        },
-                    }
-                }
-            }
-        },
-        Err(e) => {
-            Err(E::ConnectionError(e))
-        },
-    }
+    send(&client,body)
 }
 pub fn list<T:DOwned>(client:Client) -> Result<T,E> {
     let mut body=Vec::new();
-    let mut buf=vec![];
     let mut se=Serializer::new(&mut body);
-    let byte=req::plugins::loaded("plugin.loaded".to_string(),client.token.unwrap());
+    let byte=req::plugins::loaded("plugin.loaded".to_string(),client.gettoken());
     byte.serialize(&mut se).unwrap();
-    let con=connect(client.url,body,&mut buf);
-    let new_buf=buf.clone();
-    match con {
-        Ok(_) => {
-            let ret:Result<T,derror>=from_read(new_buf.as_slice());
-            match ret {
-                Ok(val) => {
-                    Ok(val)
-                },
-                Err(_) => {
-                    let ret2:Result<MsfError,derror>=from_read(new_buf.as_slice());
-                    match ret2 {
-                        Ok(val) => {
-                            Err(E::MsfError(val))
-                        },
-                        Err(e) => {
-                            Err(E::DError(e))
-                        },
-                    }
-                }
-            }
-        },
-        Err(e) => {
-            Err(E::ConnectionError(e))
-        },
-    }
+    send(&client,body)
 }
 
@@ -21,6 +21,10 @@ pub mod jobs;
 #[cfg_attr(feature="blocking",path="blocking/modules.rs")]
 pub mod modules;
 
+#[cfg_attr(feature="async",path="async/payload.rs")]
+#[cfg_attr(feature="blocking",path="blocking/payload.rs")]
+pub mod payload;
+
 #[cfg_attr(feature="async",path="async/plugins.rs")]
 #[cfg_attr(feature="blocking",path="blocking/plugins.rs")]
 pub mod plugins;
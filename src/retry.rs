@@ -0,0 +1,262 @@
+//! Retrying calls which failed because of a transient transport error.
+//!
+//! msfrpcd tends to drop connections under load,so a client can be given a [`RetryPolicy`] with
+//! [`ClientBuilder::retry`](crate::client::ClientBuilder::retry).By default only read-only calls (listings,`*.info`,
+//! `core.version`,...) are sent again,so a `module.execute` or `console.write` whose connection was reset after
+//! the server got it is not replayed.A call whose connection could not be established at all never reached the
+//! server and is always retried.
+//!
+//! ## Example
+//! ```no_run
+//! use metasploit::client::Client;
+//! use metasploit::retry::{RetryOn,RetryPolicy};
+//! use std::time::Duration;
+//! # fn main() -> Result<(),metasploit::error::Error> {
+//! let policy=RetryPolicy::new()
+//!     .max_attempts(5)
+//!     .backoff(Duration::from_millis(200),Duration::from_secs(10))
+//!     .retry_on(&[RetryOn::Connect,RetryOn::Reset,RetryOn::Timeout]);
+//! let client=Client::builder().retry(policy).login("msf","password")?;
+//! # Ok(())
+//! # }
+//! ```
+use crate::connect::method_of;
+use crate::error::ConnectionError as conerr;
+use reqwest::StatusCode;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher,Hasher};
+use std::time::Duration;
+
+/// The kinds of failure a [`RetryPolicy`] can retry.
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub enum RetryOn {
+    /// The TCP or TLS connection could not be established.
+    Connect,
+    /// The connection was reset or closed while sending the request or reading the response.
+    Reset,
+    /// The call took longer than the client's timeout.
+    Timeout,
+    /// A proxy in front of msfrpcd answered `502`,`503` or `504`.
+    Unavailable,
+}
+
+/// How often and how quickly failed calls are sent again.
+///
+/// The delay before attempt `n+1` is `initial*2^(n-1)`,capped at `max`.With jitter (the default) a random delay
+/// between half and all of that is used,so clients which failed together don't retry together.
+#[derive(Debug,Clone)]
+pub struct RetryPolicy {
+    max_attempts:u32,
+    initial:Duration,
+    max:Duration,
+    jitter:bool,
+    retry_on:Vec<RetryOn>,
+    idempotent_only:bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts:3,
+            initial:Duration::from_millis(100),
+            max:Duration::from_secs(5),
+            jitter:true,
+            retry_on:vec![RetryOn::Connect,RetryOn::Reset,RetryOn::Unavailable],
+            idempotent_only:true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Three attempts,backing off from 100ms up to 5s,on connect failures,resets and unavailable proxies.
+    pub fn new() -> Self {
+        RetryPolicy::default()
+    }
+    /// How many times a call is sent at most,including the first one.`1` disables retrying.
+    pub fn max_attempts(mut self,attempts:u32) -> Self {
+        self.max_attempts=attempts.max(1);
+        self
+    }
+    /// The delay before the first retry and the upper bound for the following ones.
+    pub fn backoff(mut self,initial:Duration,max:Duration) -> Self {
+        self.initial=initial;
+        self.max=max.max(initial);
+        self
+    }
+    pub fn jitter(mut self,jitter:bool) -> Self {
+        self.jitter=jitter;
+        self
+    }
+    /// The failures which are retried.Timeouts are not retried by default,since a call which timed out was
+    /// usually received by the server and is likely to time out again.
+    pub fn retry_on(mut self,kinds:&[RetryOn]) -> Self {
+        self.retry_on=kinds.to_vec();
+        self
+    }
+    /// Whether only read-only calls are retried.Turn it off to also replay calls which change state on the server
+    /// when they may already have been executed.
+    pub fn idempotent_only(mut self,idempotent_only:bool) -> Self {
+        self.idempotent_only=idempotent_only;
+        self
+    }
+    /// The delay before sending `body` again after its `attempt`th try ended with `result`,or `None` when it
+    /// should not be retried.
+    pub(crate) fn retry(&self,attempt:u32,result:&Result<StatusCode,conerr>,body:&[u8]) -> Option<Duration> {
+        if attempt>=self.max_attempts {
+            return None;
+        }
+        let kind=match result {
+            Ok(status) if matches!(status.as_u16(),502..=504) => RetryOn::Unavailable,
+            Ok(_) => return None,
+            Err(e) if e.is_builder() || e.is_redirect() || e.is_status() => return None,
+            Err(e) if e.is_timeout() => RetryOn::Timeout,
+            Err(e) if e.is_connect() => RetryOn::Connect,
+            Err(_) => RetryOn::Reset,
+        };
+        if !self.retry_on.contains(&kind) {
+            return None;
+        }
+        if self.idempotent_only && kind!=RetryOn::Connect && !method_of(body).is_some_and(idempotent) {
+            return None;
+        }
+        Some(self.delay(attempt))
+    }
+    fn delay(&self,attempt:u32) -> Duration {
+        let delay=self.initial.checked_mul(1<<(attempt-1).min(31)).unwrap_or(self.max).min(self.max);
+        if !self.jitter {
+            return delay;
+        }
+        let random=RandomState::new().build_hasher().finish();
+        delay/2+delay.mul_f64((random%1000) as f64/2000.0)
+    }
+}
+
+/// Calls which can be sent twice without changing the result.
+/// Reads from consoles and sessions are left out since they consume the output they return,and `auth.login` since
+/// every login the server handled issues another temporary token.
+fn idempotent(method:&str) -> bool {
+    matches!(method,
+        "auth.token_list" |
+        "console.list" | "console.tabs" |
+        "core.module_stats" | "core.thread_list" | "core.version" |
+        "db.clients" | "db.creds" | "db.current_workspace" | "db.driver" | "db.events" | "db.get_client" |
+        "db.get_host" | "db.get_note" | "db.get_ref" | "db.get_service" | "db.get_vuln" | "db.get_workspace" |
+        "db.hosts" | "db.loots" | "db.notes" | "db.services" | "db.status" | "db.vulns" | "db.workspaces" |
+        "job.info" | "job.list" |
+        "module.auxiliary" | "module.compatible_payloads" | "module.compatible_sessions" | "module.encode" |
+        "module.encoders" | "module.exploits" | "module.info" | "module.nops" | "module.options" |
+        "module.payloads" | "module.post" | "module.target_compatible_payloads" |
+        "plugin.loaded" |
+        "session.compatible_modules" | "session.list" | "session.ring_last"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+    use std::net::TcpListener;
+    use std::thread;
+
+    fn body(method:&str) -> Vec<u8> {
+        rmp_serde::to_vec(&(method,"TEST")).unwrap()
+    }
+
+    /// Posts to a local listener which handles the connection with `serve`,and returns the client's error.
+    fn error(timeout:Duration,serve:fn(std::net::TcpStream)) -> conerr {
+        let listener=TcpListener::bind("127.0.0.1:0").unwrap();
+        let url=format!("http://{}/api/",listener.local_addr().unwrap());
+        thread::spawn(move || serve(listener.accept().unwrap().0));
+        let client=reqwest::blocking::Client::builder().timeout(timeout).build().unwrap();
+        client.post(url).body(body("core.version")).send().unwrap_err()
+    }
+
+    fn connect_error() -> conerr {
+        let port=TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+        reqwest::blocking::get(format!("http://127.0.0.1:{}/api/",port)).unwrap_err()
+    }
+
+    fn reset_error() -> conerr {
+        error(Duration::from_secs(5),|mut stream| {
+            let _=stream.read(&mut [0;1024]);
+        })
+    }
+
+    fn timeout_error() -> conerr {
+        error(Duration::from_millis(50),|stream| {
+            thread::sleep(Duration::from_millis(500));
+            drop(stream);
+        })
+    }
+
+    fn status(code:u16) -> Result<StatusCode,conerr> {
+        Ok(StatusCode::from_u16(code).unwrap())
+    }
+
+    #[test]
+    fn max_attempts() {
+        let policy=RetryPolicy::new().max_attempts(3).jitter(false);
+        let version=body("core.version");
+        assert!(policy.retry(1,&status(503),&version).is_some());
+        assert!(policy.retry(2,&status(503),&version).is_some());
+        assert_eq!(policy.retry(3,&status(503),&version),None);
+        assert_eq!(RetryPolicy::new().max_attempts(0).retry(1,&status(503),&version),None);
+    }
+
+    #[test]
+    fn delay_backs_off_up_to_max() {
+        let policy=RetryPolicy::new().backoff(Duration::from_millis(100),Duration::from_secs(1)).jitter(false);
+        let delays:Vec<u128>=(1..=6).map(|attempt| policy.delay(attempt).as_millis()).collect();
+        assert_eq!(delays,[100,200,400,800,1000,1000]);
+        assert_eq!(policy.delay(40),Duration::from_secs(1));
+        let policy=RetryPolicy::new().backoff(Duration::from_secs(2),Duration::from_secs(1)).jitter(false);
+        assert_eq!(policy.delay(1),Duration::from_secs(2));
+    }
+
+    #[test]
+    fn jitter_stays_in_range() {
+        let policy=RetryPolicy::new().backoff(Duration::from_millis(100),Duration::from_millis(300));
+        for attempt in 1..=4 {
+            let full=[100,200,300,300][attempt as usize-1];
+            for _ in 0..100 {
+                let delay=policy.delay(attempt).as_millis();
+                assert!(delay>=full/2 && delay<=full,"attempt {}: {}ms",attempt,delay);
+            }
+        }
+    }
+
+    #[test]
+    fn unavailable_statuses() {
+        let policy=RetryPolicy::new().retry_on(&[RetryOn::Unavailable]).jitter(false);
+        let version=body("core.version");
+        for code in [502,503,504] {
+            assert_eq!(policy.retry(1,&status(code),&version),Some(Duration::from_millis(100)),"{}",code);
+        }
+        for code in [200,401,500,501,505] {
+            assert_eq!(policy.retry(1,&status(code),&version),None,"{}",code);
+        }
+        assert_eq!(RetryPolicy::new().retry_on(&[RetryOn::Connect]).retry(1,&status(503),&version),None);
+    }
+
+    #[test]
+    fn idempotent_only() {
+        let policy=RetryPolicy::new().retry_on(&[RetryOn::Connect,RetryOn::Reset,RetryOn::Timeout,RetryOn::Unavailable]);
+        let (execute,version)=(body("module.execute"),body("core.version"));
+        let (connect,reset,timeout)=(Err(connect_error()),Err(reset_error()),Err(timeout_error()));
+        for (kind,result) in [(RetryOn::Connect,&connect),(RetryOn::Reset,&reset),(RetryOn::Timeout,&timeout)] {
+            let only=RetryPolicy::new().retry_on(&[kind]);
+            assert!(only.retry(1,result,&version).is_some(),"{:?}",kind);
+            assert_eq!([&connect,&reset,&timeout].iter().filter(|other| only.retry(1,other,&version).is_some()).count(),1,"{:?}",kind);
+        }
+        assert!(policy.retry(1,&connect,&execute).is_some());
+        for result in [&reset,&timeout,&status(502)] {
+            assert_eq!(policy.retry(1,result,&execute),None,"{:?}",result);
+            assert!(policy.retry(1,result,&version).is_some(),"{:?}",result);
+            assert_eq!(policy.retry(1,result,&body("auth.login")),None,"{:?}",result);
+        }
+        let replay=policy.idempotent_only(false);
+        for result in [&connect,&reset,&timeout,&status(502)] {
+            assert!(replay.retry(1,result,&execute).is_some(),"{:?}",result);
+        }
+    }
+}
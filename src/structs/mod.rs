@@ -1 +1,3 @@
 pub mod request;
+pub mod response;
+pub mod query;
@@ -0,0 +1,353 @@
+//! Filters for the database listings in [`msf::db`](crate::msf::db).
+//!
+//! A query only sends the options which were set,so [`HostQuery::new()`] lists what msfrpcd returns by default:
+//! the first 100 records of the current workspace.Each option is sent with the type msfrpcd expects,e.g. `limit`
+//! as an integer and `ports` as a port list such as `"22,80,8000-8100"`.
+//!
+//! ## Example
+//! ```no_run
+//! use metasploit::client::Client;
+//! use metasploit::msf::db;
+//! use metasploit::query::ServiceQuery;
+//! # #[cfg(not(feature="async"))]
+//! # fn main() -> Result<(),metasploit::error::Error> {
+//! let client=Client::builder().login("msf","password")?;
+//! let query=ServiceQuery::new().workspace("acme").only_up(true).proto("tcp").ports(1..=1024).port(8080);
+//! for service in db::services_typed(client,query)? {
+//!     println!("{}:{} {}",service.host,service.port,service.name);
+//! }
+//! # Ok(())
+//! # }
+//! # #[cfg(feature="async")]
+//! # fn main() {}
+//! ```
+use crate::value::Value;
+use serde::{Serialize,Serializer};
+use std::ops::RangeInclusive;
+
+/// The options every listing takes.
+#[derive(Debug,Clone,Default,PartialEq,Eq)]
+struct Page {
+    workspace:Option<String>,
+    limit:Option<u32>,
+    offset:Option<u32>,
+}
+
+impl Page {
+    fn entries(&self) -> Vec<(&'static str,Value)> {
+        let mut entries=Vec::new();
+        if let Some(workspace)=&self.workspace {
+            entries.push(("workspace",Value::from(workspace.as_str())));
+        }
+        if let Some(limit)=self.limit {
+            entries.push(("limit",Value::from(limit)));
+        }
+        if let Some(offset)=self.offset {
+            entries.push(("offset",Value::from(offset)));
+        }
+        entries
+    }
+}
+
+/// Adds the constructor,the setters every listing takes and `Serialize` through `entries`.
+macro_rules! query {
+    ($query:ident) => {
+        impl $query {
+            pub fn new() -> Self {
+                $query::default()
+            }
+            /// The workspace to list instead of the current one.
+            pub fn workspace(mut self,workspace:&str) -> Self {
+                self.page.workspace=Some(workspace.to_string());
+                self
+            }
+            /// How many records are returned at most.msfrpcd returns 100 when no limit is given.
+            pub fn limit(mut self,limit:u32) -> Self {
+                self.page.limit=Some(limit);
+                self
+            }
+            /// How many records are skipped.
+            pub fn offset(mut self,offset:u32) -> Self {
+                self.page.offset=Some(offset);
+                self
+            }
+        }
+
+        impl Serialize for $query {
+            fn serialize<S:Serializer>(&self,serializer:S) -> Result<S::Ok,S::Error> {
+                let entries=self.entries().into_iter().map(|(name,value)| (Value::from(name),value)).collect();
+                Value::Map(entries).serialize(serializer)
+            }
+        }
+    };
+}
+
+fn addresses(addresses:&[String]) -> Value {
+    Value::Array(addresses.iter().map(|address| Value::from(address.as_str())).collect())
+}
+
+/// Ports in msfrpcd's port list format,e.g. `22,8000-8100`.
+fn ports(ports:&[RangeInclusive<u16>]) -> Value {
+    let ports:Vec<String>=ports.iter().map(|range| match range.start()==range.end() {
+        true => range.start().to_string(),
+        false => format!("{}-{}",range.start(),range.end()),
+    }).collect();
+    Value::from(ports.join(","))
+}
+
+/// The filters of `db.hosts`.
+#[derive(Debug,Clone,Default,PartialEq,Eq)]
+pub struct HostQuery {
+    page:Page,
+    only_up:bool,
+    addresses:Vec<String>,
+}
+query!(HostQuery);
+
+impl HostQuery {
+    /// Only lists hosts which are alive or in an unknown state.
+    pub fn only_up(mut self,only_up:bool) -> Self {
+        self.only_up=only_up;
+        self
+    }
+    /// Only lists this address.Can be called again to list several addresses.
+    pub fn address(mut self,address:&str) -> Self {
+        self.addresses.push(address.to_string());
+        self
+    }
+    fn entries(&self) -> Vec<(&'static str,Value)> {
+        let mut entries=self.page.entries();
+        if self.only_up {
+            entries.push(("only_up",Value::from(true)));
+        }
+        if !self.addresses.is_empty() {
+            entries.push(("addresses",addresses(&self.addresses)));
+        }
+        entries
+    }
+}
+
+/// The filters of `db.services`.
+#[derive(Debug,Clone,Default,PartialEq,Eq)]
+pub struct ServiceQuery {
+    page:Page,
+    only_up:bool,
+    addresses:Vec<String>,
+    ports:Vec<RangeInclusive<u16>>,
+    proto:Option<String>,
+    names:Vec<String>,
+}
+query!(ServiceQuery);
+
+impl ServiceQuery {
+    /// Only lists open services.
+    pub fn only_up(mut self,only_up:bool) -> Self {
+        self.only_up=only_up;
+        self
+    }
+    /// Only lists services on this address.Can be called again to list several addresses.
+    pub fn address(mut self,address:&str) -> Self {
+        self.addresses.push(address.to_string());
+        self
+    }
+    /// Only lists services on this port.Can be combined with other ports and ranges.
+    pub fn port(self,port:u16) -> Self {
+        self.ports(port..=port)
+    }
+    /// Only lists services on a port in this range.
+    pub fn ports(mut self,ports:RangeInclusive<u16>) -> Self {
+        self.ports.push(ports);
+        self
+    }
+    /// Only lists services using this protocol,`tcp` or `udp`.
+    pub fn proto(mut self,proto:&str) -> Self {
+        self.proto=Some(proto.to_string());
+        self
+    }
+    /// Only lists services with this name,e.g. `smb`.Can be called again to list several names.
+    pub fn name(mut self,name:&str) -> Self {
+        self.names.push(name.to_string());
+        self
+    }
+    fn entries(&self) -> Vec<(&'static str,Value)> {
+        let mut entries=self.page.entries();
+        if self.only_up {
+            entries.push(("only_up",Value::from(true)));
+        }
+        if !self.addresses.is_empty() {
+            entries.push(("addresses",addresses(&self.addresses)));
+        }
+        if !self.ports.is_empty() {
+            entries.push(("ports",ports(&self.ports)));
+        }
+        if let Some(proto)=&self.proto {
+            entries.push(("proto",Value::from(proto.as_str())));
+        }
+        if !self.names.is_empty() {
+            entries.push(("names",Value::from(self.names.join(","))));
+        }
+        entries
+    }
+}
+
+/// The filters of `db.vulns`.
+#[derive(Debug,Clone,Default,PartialEq,Eq)]
+pub struct VulnQuery {
+    page:Page,
+    addresses:Vec<String>,
+    ports:Vec<RangeInclusive<u16>>,
+    proto:Option<String>,
+    names:Vec<String>,
+}
+query!(VulnQuery);
+
+impl VulnQuery {
+    /// Only lists vulnerabilities of this address.Can be called again to list several addresses.
+    pub fn address(mut self,address:&str) -> Self {
+        self.addresses.push(address.to_string());
+        self
+    }
+    /// Only lists vulnerabilities of services on this port.
+    pub fn port(self,port:u16) -> Self {
+        self.ports(port..=port)
+    }
+    /// Only lists vulnerabilities of services on a port in this range.
+    pub fn ports(mut self,ports:RangeInclusive<u16>) -> Self {
+        self.ports.push(ports);
+        self
+    }
+    /// Only lists vulnerabilities of services using this protocol,`tcp` or `udp`.
+    pub fn proto(mut self,proto:&str) -> Self {
+        self.proto=Some(proto.to_string());
+        self
+    }
+    /// Only lists vulnerabilities with this name.Can be called again to list several names.
+    pub fn name(mut self,name:&str) -> Self {
+        self.names.push(name.to_string());
+        self
+    }
+    fn entries(&self) -> Vec<(&'static str,Value)> {
+        let mut entries=self.page.entries();
+        if !self.addresses.is_empty() {
+            entries.push(("addresses",addresses(&self.addresses)));
+        }
+        if !self.ports.is_empty() {
+            entries.push(("ports",ports(&self.ports)));
+        }
+        if let Some(proto)=&self.proto {
+            entries.push(("proto",Value::from(proto.as_str())));
+        }
+        if !self.names.is_empty() {
+            entries.push(("names",Value::from(self.names.join(","))));
+        }
+        entries
+    }
+}
+
+/// The filters of `db.notes`.
+#[derive(Debug,Clone,Default,PartialEq,Eq)]
+pub struct NoteQuery {
+    page:Page,
+    addresses:Vec<String>,
+    ports:Vec<RangeInclusive<u16>>,
+    proto:Option<String>,
+    note_type:Option<String>,
+}
+query!(NoteQuery);
+
+impl NoteQuery {
+    /// Only lists notes about this address.Can be called again to list several addresses.
+    pub fn address(mut self,address:&str) -> Self {
+        self.addresses.push(address.to_string());
+        self
+    }
+    /// Only lists notes about services on this port.
+    pub fn port(self,port:u16) -> Self {
+        self.ports(port..=port)
+    }
+    /// Only lists notes about services on a port in this range.
+    pub fn ports(mut self,ports:RangeInclusive<u16>) -> Self {
+        self.ports.push(ports);
+        self
+    }
+    /// Only lists notes about services using this protocol,`tcp` or `udp`.
+    pub fn proto(mut self,proto:&str) -> Self {
+        self.proto=Some(proto.to_string());
+        self
+    }
+    /// Only lists notes of this type,e.g. `smb.fingerprint`.
+    pub fn note_type(mut self,note_type:&str) -> Self {
+        self.note_type=Some(note_type.to_string());
+        self
+    }
+    fn entries(&self) -> Vec<(&'static str,Value)> {
+        let mut entries=self.page.entries();
+        if !self.addresses.is_empty() {
+            entries.push(("addresses",addresses(&self.addresses)));
+        }
+        if !self.ports.is_empty() {
+            entries.push(("ports",ports(&self.ports)));
+        }
+        if let Some(proto)=&self.proto {
+            entries.push(("proto",Value::from(proto.as_str())));
+        }
+        if let Some(note_type)=&self.note_type {
+            entries.push(("ntype",Value::from(note_type.as_str())));
+        }
+        entries
+    }
+}
+
+/// The filters of `db.clients`.
+#[derive(Debug,Clone,Default,PartialEq,Eq)]
+pub struct ClientQuery {
+    page:Page,
+    addresses:Vec<String>,
+    ua_name:Option<String>,
+    ua_ver:Option<String>,
+}
+query!(ClientQuery);
+
+impl ClientQuery {
+    /// Only lists browsers on this address.Can be called again to list several addresses.
+    pub fn address(mut self,address:&str) -> Self {
+        self.addresses.push(address.to_string());
+        self
+    }
+    /// Only lists this browser,e.g. `Firefox`.
+    pub fn ua_name(mut self,ua_name:&str) -> Self {
+        self.ua_name=Some(ua_name.to_string());
+        self
+    }
+    /// Only lists this browser version.
+    pub fn ua_ver(mut self,ua_ver:&str) -> Self {
+        self.ua_ver=Some(ua_ver.to_string());
+        self
+    }
+    fn entries(&self) -> Vec<(&'static str,Value)> {
+        let mut entries=self.page.entries();
+        if !self.addresses.is_empty() {
+            entries.push(("addresses",addresses(&self.addresses)));
+        }
+        if let Some(ua_name)=&self.ua_name {
+            entries.push(("ua_name",Value::from(ua_name.as_str())));
+        }
+        if let Some(ua_ver)=&self.ua_ver {
+            entries.push(("ua_ver",Value::from(ua_ver.as_str())));
+        }
+        entries
+    }
+}
+
+/// The options of `db.events`,`db.loots` and `db.creds`,which can't be filtered further.
+#[derive(Debug,Clone,Default,PartialEq,Eq)]
+pub struct ListQuery {
+    page:Page,
+}
+query!(ListQuery);
+
+impl ListQuery {
+    fn entries(&self) -> Vec<(&'static str,Value)> {
+        self.page.entries()
+    }
+}
@@ -3,7 +3,7 @@ use serde::Serialize as se;
 use std::collections::HashMap;
 
 #[derive(se)]
-pub struct hosts(pub String,pub String);
+pub struct list<Q>(pub String,pub String,pub Q);
 #[derive(se)]
 pub struct workspaces(pub String,pub String);
 #[derive(se)]
@@ -11,8 +11,6 @@ pub struct current_workspace(pub String,pub String);
 #[derive(se)]
 pub struct gsda_workspace(pub String,pub String,pub String);
 #[derive(se)]
-pub struct nc(pub String,pub String,pub HashMap<String,String>);
-#[derive(se)]
 pub struct grd_host(pub String,pub String,pub HashMap<String,String>);
 #[derive(se)]
 pub struct disconnect(pub String,pub String);
@@ -1,5 +1,6 @@
 #![allow(non_camel_case_types)]
 use serde::Serialize as se;
+use crate::value::Value;
 use std::collections::HashMap;
 
 #[derive(se)]
@@ -16,3 +17,5 @@ pub struct options(pub String,pub String,pub String,pub String);
 pub struct compactible(pub String,pub String,pub String);
 #[derive(se)]
 pub struct compactible_tp(pub String,pub String,pub String,pub i32);
+#[derive(se)]
+pub struct generate(pub String,pub String,pub String,pub String,pub Value);
@@ -0,0 +1,41 @@
+use serde::{Deserialize,Serialize};
+
+/// The reply to `core.version`.
+#[derive(Serialize,Deserialize,Debug,Clone,PartialEq,Eq,Default)]
+#[serde(default)]
+pub struct VersionInfo {
+    /// The framework version,e.g. `6.3.25-dev`.
+    pub version:String,
+    /// The Ruby version and platform the framework runs on.
+    pub ruby:String,
+    /// The RPC API version.Empty when an older server doesn't send it.
+    pub api:String,
+}
+
+/// The number of modules loaded per type,as replied to `core.module_stats`,`core.reload_modules` and
+/// `core.add_module_path`.
+#[derive(Serialize,Deserialize,Debug,Clone,Copy,PartialEq,Eq,Default)]
+#[serde(default)]
+pub struct ModuleStats {
+    pub exploits:u32,
+    pub auxiliary:u32,
+    pub post:u32,
+    pub encoders:u32,
+    pub nops:u32,
+    pub payloads:u32,
+    /// Evasion modules,counted since Metasploit 5.
+    pub evasion:u32,
+}
+
+/// A framework thread,as listed by `core.thread_list`.
+#[derive(Serialize,Deserialize,Debug,Clone,PartialEq,Eq,Default)]
+#[serde(default)]
+pub struct ThreadInfo {
+    /// The thread's state as reported by Ruby,e.g. `sleep` or `run`.
+    pub status:String,
+    /// Whether the framework stops when the thread dies.
+    pub critical:bool,
+    pub name:String,
+    /// When the thread was started,e.g. `2023-07-22 04:26:40 +0000`.
+    pub started:String,
+}
@@ -0,0 +1,194 @@
+//! Records from the framework's database.Timestamps are seconds since the Unix epoch.
+use serde::{Deserialize,Deserializer,Serialize};
+
+/// Fields the database may leave `nil`,which are then left empty.
+fn nullable<'de,D:Deserializer<'de>,T:Deserialize<'de>+Default>(deserializer:D) -> Result<T,D::Error> {
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+/// A host,as listed by `db.hosts` and `db.get_host`.
+#[derive(Serialize,Deserialize,Debug,Clone,PartialEq,Eq,Default)]
+#[serde(default)]
+pub struct Host {
+    pub address:String,
+    #[serde(deserialize_with="nullable")]
+    pub mac:String,
+    /// The host name,if one was resolved or reported.
+    #[serde(deserialize_with="nullable")]
+    pub name:String,
+    /// `alive`,`down` or `unknown`.
+    #[serde(deserialize_with="nullable")]
+    pub state:String,
+    #[serde(deserialize_with="nullable")]
+    pub os_name:String,
+    #[serde(deserialize_with="nullable")]
+    pub os_flavor:String,
+    #[serde(deserialize_with="nullable")]
+    pub os_sp:String,
+    #[serde(deserialize_with="nullable")]
+    pub os_lang:String,
+    /// `server`,`client`,`device` and so on.
+    #[serde(deserialize_with="nullable")]
+    pub purpose:String,
+    #[serde(deserialize_with="nullable")]
+    pub info:String,
+    #[serde(deserialize_with="nullable")]
+    pub created_at:i64,
+    #[serde(deserialize_with="nullable")]
+    pub updated_at:i64,
+}
+
+/// A service on a host,as listed by `db.services` and `db.get_service`.
+#[derive(Serialize,Deserialize,Debug,Clone,PartialEq,Eq,Default)]
+#[serde(default)]
+pub struct Service {
+    /// The address of the host.
+    pub host:String,
+    pub port:u16,
+    /// `tcp` or `udp`.
+    pub proto:String,
+    /// `open`,`closed`,`filtered` or `unknown`.
+    #[serde(deserialize_with="nullable")]
+    pub state:String,
+    /// The service name,e.g. `smb`.
+    #[serde(deserialize_with="nullable")]
+    pub name:String,
+    /// The banner or version found for the service.
+    #[serde(deserialize_with="nullable")]
+    pub info:String,
+    #[serde(deserialize_with="nullable")]
+    pub created_at:i64,
+    #[serde(deserialize_with="nullable")]
+    pub updated_at:i64,
+}
+
+/// A vulnerability found on a host,as listed by `db.vulns` and `db.get_vuln`.
+#[derive(Serialize,Deserialize,Debug,Clone,PartialEq,Eq,Default)]
+#[serde(default)]
+pub struct Vuln {
+    pub host:String,
+    /// The port of the affected service,if any.
+    #[serde(deserialize_with="nullable")]
+    pub port:Option<u16>,
+    #[serde(deserialize_with="nullable")]
+    pub proto:Option<String>,
+    pub name:String,
+    /// The references,e.g. `CVE-2017-0144`.The server sends them comma separated.
+    #[serde(deserialize_with="refs")]
+    pub refs:Vec<String>,
+    #[serde(deserialize_with="nullable")]
+    pub time:i64,
+}
+
+fn refs<'de,D:Deserializer<'de>>(deserializer:D) -> Result<Vec<String>,D::Error> {
+    let refs:String=nullable(deserializer)?;
+    Ok(refs.split(',').map(str::trim).filter(|r| !r.is_empty()).map(String::from).collect())
+}
+
+/// A note attached to a host or service,as listed by `db.notes` and `db.get_note`.
+#[derive(Serialize,Deserialize,Debug,Clone,PartialEq,Eq,Default)]
+#[serde(default)]
+pub struct Note {
+    #[serde(deserialize_with="nullable")]
+    pub host:String,
+    /// The service name,if the note is about a service.
+    #[serde(deserialize_with="nullable")]
+    pub service:String,
+    /// What the note is about,e.g. `smb.fingerprint`.
+    #[serde(rename="type")]
+    pub note_type:String,
+    /// The note's data,as Ruby's `inspect` output.
+    #[serde(deserialize_with="nullable")]
+    pub data:String,
+    #[serde(deserialize_with="nullable")]
+    pub time:i64,
+}
+
+/// A piece of loot,e.g. a downloaded file or dumped hashes,as listed by `db.loots`.
+#[derive(Serialize,Deserialize,Debug,Clone,PartialEq,Eq,Default)]
+#[serde(default)]
+pub struct Loot {
+    #[serde(deserialize_with="nullable")]
+    pub host:String,
+    #[serde(deserialize_with="nullable")]
+    pub service:String,
+    /// The loot type,e.g. `windows.hashes`.
+    pub ltype:String,
+    /// The content type,e.g. `text/plain`.
+    #[serde(deserialize_with="nullable")]
+    pub ctype:String,
+    #[serde(deserialize_with="nullable")]
+    pub data:String,
+    /// The file name.
+    #[serde(deserialize_with="nullable")]
+    pub name:String,
+    #[serde(deserialize_with="nullable")]
+    pub info:String,
+    #[serde(deserialize_with="nullable")]
+    pub created_at:i64,
+    #[serde(deserialize_with="nullable")]
+    pub updated_at:i64,
+}
+
+/// A credential,as listed by `db.creds`.
+#[derive(Serialize,Deserialize,Debug,Clone,PartialEq,Eq,Default)]
+#[serde(default)]
+pub struct Cred {
+    pub host:String,
+    #[serde(deserialize_with="nullable")]
+    pub port:u16,
+    #[serde(deserialize_with="nullable")]
+    pub proto:String,
+    /// The name of the service the credential is for,e.g. `smb`.
+    #[serde(deserialize_with="nullable")]
+    pub sname:String,
+    /// `password`,`ntlm_hash`,`ssh_key` and so on.
+    #[serde(rename="type",deserialize_with="nullable")]
+    pub cred_type:String,
+    #[serde(deserialize_with="nullable")]
+    pub user:String,
+    #[serde(deserialize_with="nullable")]
+    pub pass:String,
+    #[serde(deserialize_with="nullable")]
+    pub active:bool,
+    #[serde(deserialize_with="nullable")]
+    pub updated_at:i64,
+}
+
+/// Something that happened in the framework,e.g. a module run,as listed by `db.events`.
+#[derive(Serialize,Deserialize,Debug,Clone,PartialEq,Eq,Default)]
+#[serde(default)]
+pub struct Event {
+    #[serde(deserialize_with="nullable")]
+    pub host:String,
+    pub name:String,
+    #[serde(deserialize_with="nullable")]
+    pub critical:bool,
+    /// The framework user who caused the event.
+    #[serde(deserialize_with="nullable")]
+    pub username:String,
+    /// Details,as Ruby's `inspect` output.
+    #[serde(deserialize_with="nullable")]
+    pub info:String,
+    #[serde(deserialize_with="nullable")]
+    pub created_at:i64,
+    #[serde(deserialize_with="nullable")]
+    pub updated_at:i64,
+}
+
+/// A browser which visited one of the framework's web servers,as listed by `db.clients` and `db.get_client`.
+#[derive(Serialize,Deserialize,Debug,Clone,PartialEq,Eq,Default)]
+#[serde(default)]
+pub struct WebClient {
+    pub host:String,
+    #[serde(deserialize_with="nullable")]
+    pub ua_string:String,
+    #[serde(deserialize_with="nullable")]
+    pub ua_name:String,
+    #[serde(deserialize_with="nullable")]
+    pub ua_ver:String,
+    #[serde(deserialize_with="nullable")]
+    pub created_at:i64,
+    #[serde(deserialize_with="nullable")]
+    pub updated_at:i64,
+}
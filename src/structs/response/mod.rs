@@ -0,0 +1,49 @@
+//! Typed replies of the RPC methods,returned by the `*_typed` functions in [`msf`](crate::msf).
+//!
+//! They can also be passed as `T` to the generic functions.Fields which older Metasploit versions don't send
+//! fall back to their default.
+pub mod core;
+pub mod db;
+pub mod modules;
+pub mod sessions;
+
+use serde::de::{self,Deserialize,Deserializer,Unexpected,Visitor};
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use std::fmt;
+
+/// An id keying a map,e.g. a thread or session id.msfrpcd sends it as an integer,but JSON-RPC only has string keys.
+#[derive(Debug,Clone,Copy,PartialEq,Eq,PartialOrd,Ord)]
+struct Id(u32);
+
+impl<'de> Deserialize<'de> for Id {
+    fn deserialize<D:Deserializer<'de>>(deserializer:D) -> Result<Self,D::Error> {
+        struct IdVisitor;
+        impl<'de> Visitor<'de> for IdVisitor {
+            type Value=Id;
+            fn expecting(&self,f:&mut fmt::Formatter) -> fmt::Result {
+                f.write_str("an id as an integer or a string")
+            }
+            fn visit_u64<E:de::Error>(self,id:u64) -> Result<Id,E> {
+                u32::try_from(id).map(Id).map_err(|_| E::invalid_value(Unexpected::Unsigned(id),&self))
+            }
+            fn visit_i64<E:de::Error>(self,id:i64) -> Result<Id,E> {
+                u32::try_from(id).map(Id).map_err(|_| E::invalid_value(Unexpected::Signed(id),&self))
+            }
+            fn visit_str<E:de::Error>(self,id:&str) -> Result<Id,E> {
+                id.parse().map(Id).map_err(|_| E::invalid_value(Unexpected::Str(id),&self))
+            }
+        }
+        deserializer.deserialize_any(IdVisitor)
+    }
+}
+
+/// A map keyed by ids,decoded from integer keys as well as from the string keys of a JSON-RPC reply.
+pub(crate) struct IdMap<V>(pub BTreeMap<u32,V>);
+
+impl<'de,V:Deserialize<'de>> Deserialize<'de> for IdMap<V> {
+    fn deserialize<D:Deserializer<'de>>(deserializer:D) -> Result<Self,D::Error> {
+        let map=BTreeMap::<Id,V>::deserialize(deserializer)?;
+        Ok(IdMap(map.into_iter().map(|(Id(id),value)| (id,value)).collect()))
+    }
+}
@@ -0,0 +1,159 @@
+use crate::structs::response::IdMap;
+use crate::value::Value;
+use serde::{Deserialize,Deserializer,Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// A module's description,as replied to `module.info`.
+///
+/// Fields missing from the reply,as with older Metasploit versions,are left empty.
+#[derive(Serialize,Deserialize,Debug,Clone,PartialEq,Default)]
+#[serde(default)]
+pub struct ModuleInfo {
+    /// The module type,e.g. `exploit`.
+    #[serde(rename="type")]
+    pub module_type:String,
+    /// The module's title,e.g. `MS17-010 EternalBlue SMB Remote Windows Kernel Pool Corruption`.
+    pub name:String,
+    /// The module's path including its type,e.g. `exploit/windows/smb/ms17_010_eternalblue`.
+    pub fullname:String,
+    /// How reliable the module is,e.g. `excellent` or `normal`.
+    pub rank:String,
+    pub disclosuredate:String,
+    pub description:String,
+    pub license:String,
+    pub filepath:String,
+    pub arch:Vec<String>,
+    pub platform:Vec<String>,
+    pub authors:Vec<String>,
+    pub privileged:bool,
+    /// Whether the module implements `check`.
+    pub check:bool,
+    pub references:Vec<ModuleReference>,
+    #[serde(deserialize_with="targets")]
+    pub targets:Vec<ModuleTarget>,
+    pub default_target:Option<u32>,
+    /// `aggressive` for modules attacking a target,`passive` for those waiting for one.
+    pub stance:String,
+    /// The actions of auxiliary and post modules by index.
+    #[serde(deserialize_with="actions")]
+    pub actions:BTreeMap<u32,String>,
+    pub default_action:Option<String>,
+    pub options:BTreeMap<String,ModuleOption>,
+}
+
+/// An advisory or article about a module,e.g. `CVE` `2017-0144`.It is sent as a `[kind,value]` pair.
+#[derive(Serialize,Deserialize,Debug,Clone,PartialEq,Eq,Default)]
+pub struct ModuleReference {
+    /// The reference type,e.g. `CVE`,`MSB`,`EDB` or `URL`.
+    pub kind:String,
+    pub value:String,
+}
+
+/// A target of an exploit,e.g. `Windows 7 SP1 x64`.
+#[derive(Serialize,Deserialize,Debug,Clone,PartialEq,Eq,Default)]
+pub struct ModuleTarget {
+    /// The index to set as the `TARGET` option.
+    pub index:u32,
+    pub name:String,
+}
+
+/// The targets are sent as a map from index to name.
+fn targets<'de,D:Deserializer<'de>>(deserializer:D) -> Result<Vec<ModuleTarget>,D::Error> {
+    let targets=IdMap::<String>::deserialize(deserializer)?;
+    Ok(targets.0.into_iter().map(|(index,name)| ModuleTarget{index,name}).collect())
+}
+
+/// The actions are sent as a map from index to name as well.
+fn actions<'de,D:Deserializer<'de>>(deserializer:D) -> Result<BTreeMap<u32,String>,D::Error> {
+    IdMap::deserialize(deserializer).map(|actions| actions.0)
+}
+
+/// A datastore option of a module,as replied to `module.options` and in [`ModuleInfo::options`].
+#[derive(Serialize,Deserialize,Debug,Clone,PartialEq,Default)]
+#[serde(default)]
+pub struct ModuleOption {
+    #[serde(rename="type")]
+    pub option_type:OptionType,
+    pub required:bool,
+    /// Advanced options are hidden by `show options` in msfconsole.
+    pub advanced:bool,
+    pub evasion:bool,
+    pub desc:String,
+    /// The value used when the option isn't set,typed according to `option_type`.
+    pub default:Option<Value>,
+    /// The allowed values of an [`OptionType::Enum`] option.
+    pub enums:Vec<String>,
+}
+
+/// The type of a module option,which tells how its value is validated.
+#[derive(Serialize,Deserialize,Debug,Clone,PartialEq,Eq,Hash,Default)]
+#[serde(from="String",into="String")]
+pub enum OptionType {
+    #[default]
+    String,
+    Bool,
+    Port,
+    Address,
+    /// One or more addresses,ranges or CIDR blocks,e.g. `10.0.0.1-10.0.0.20`.
+    AddressRange,
+    Path,
+    Enum,
+    Int,
+    Float,
+    /// Raw bytes,e.g. a shellcode prefix.
+    Raw,
+    /// Target hosts given like [`OptionType::AddressRange`],or as URLs or `file:` lists.
+    Rhosts,
+    Regexp,
+    /// An option type this crate doesn't know yet.
+    Other(String),
+}
+
+impl From<String> for OptionType {
+    fn from(kind:String) -> Self {
+        match kind.as_str() {
+            "string" => OptionType::String,
+            "bool" => OptionType::Bool,
+            "port" => OptionType::Port,
+            "address" => OptionType::Address,
+            "addressrange" => OptionType::AddressRange,
+            "path" => OptionType::Path,
+            "enum" => OptionType::Enum,
+            "integer" | "int" => OptionType::Int,
+            "float" => OptionType::Float,
+            "raw" => OptionType::Raw,
+            "rhosts" => OptionType::Rhosts,
+            "regexp" => OptionType::Regexp,
+            _ => OptionType::Other(kind),
+        }
+    }
+}
+
+impl From<OptionType> for String {
+    fn from(kind:OptionType) -> Self {
+        kind.to_string()
+    }
+}
+
+/// The name Metasploit uses for the type.
+impl fmt::Display for OptionType {
+    fn fmt(&self,f:&mut fmt::Formatter) -> fmt::Result {
+        let name=match self {
+            OptionType::String => "string",
+            OptionType::Bool => "bool",
+            OptionType::Port => "port",
+            OptionType::Address => "address",
+            OptionType::AddressRange => "addressrange",
+            OptionType::Path => "path",
+            OptionType::Enum => "enum",
+            OptionType::Int => "integer",
+            OptionType::Float => "float",
+            OptionType::Raw => "raw",
+            OptionType::Rhosts => "rhosts",
+            OptionType::Regexp => "regexp",
+            OptionType::Other(kind) => kind,
+        };
+        write!(f,"{}",name)
+    }
+}
@@ -0,0 +1,82 @@
+use serde::{Deserialize,Serialize};
+use std::fmt;
+
+/// The kind of a session,from the `type` field of `session.list`.
+#[derive(Serialize,Deserialize,Debug,Clone,PartialEq,Eq,Hash)]
+#[serde(from="String",into="String")]
+pub enum SessionType {
+    Shell,
+    Meterpreter,
+    Powershell,
+    /// A VNC session opened by a VNC inject payload.
+    Vnc,
+    /// A session kind this crate doesn't know yet,e.g. from a plugin.
+    Other(String),
+}
+
+impl Default for SessionType {
+    fn default() -> Self {
+        SessionType::Other(String::new())
+    }
+}
+
+impl From<String> for SessionType {
+    fn from(kind:String) -> Self {
+        match kind.as_str() {
+            "shell" => SessionType::Shell,
+            "meterpreter" => SessionType::Meterpreter,
+            "powershell" => SessionType::Powershell,
+            "vnc" => SessionType::Vnc,
+            _ => SessionType::Other(kind),
+        }
+    }
+}
+
+impl From<SessionType> for String {
+    fn from(kind:SessionType) -> Self {
+        kind.to_string()
+    }
+}
+
+impl fmt::Display for SessionType {
+    fn fmt(&self,f:&mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SessionType::Shell => write!(f,"shell"),
+            SessionType::Meterpreter => write!(f,"meterpreter"),
+            SessionType::Powershell => write!(f,"powershell"),
+            SessionType::Vnc => write!(f,"vnc"),
+            SessionType::Other(kind) => write!(f,"{}",kind),
+        }
+    }
+}
+
+/// An open session,as listed by `session.list`.
+///
+/// Fields missing from the reply,as with older Metasploit versions,are left empty.
+#[derive(Serialize,Deserialize,Debug,Clone,PartialEq,Eq,Default)]
+#[serde(default)]
+pub struct SessionInfo {
+    #[serde(rename="type")]
+    pub session_type:SessionType,
+    /// The local end of the session's connection,e.g. `10.0.0.2:4444`.
+    pub tunnel_local:String,
+    /// The remote end of the session's connection,e.g. `10.0.0.5:49152`.
+    pub tunnel_peer:String,
+    pub via_exploit:String,
+    pub via_payload:String,
+    pub desc:String,
+    /// What the session reports about itself,e.g. `NT AUTHORITY\SYSTEM @ WIN-TARGET` for meterpreter.
+    pub info:String,
+    pub workspace:String,
+    pub session_host:String,
+    pub session_port:u16,
+    pub target_host:String,
+    /// The framework user who opened the session.
+    pub username:String,
+    pub uuid:String,
+    pub exploit_uuid:String,
+    /// Networks routed through the session,comma separated.
+    pub routes:String,
+    pub arch:String,
+    pub platform:String,
+}
@@ -0,0 +1,212 @@
+//! TLS settings used when the client talks to msfrpcd over https.
+//!
+//! By default the server certificate is verified against the system's root certificates.
+//! A certificate signed by your own CA can be trusted with [`TlsConfig::add_ca_file`],a self-signed one can be
+//! pinned by its SHA-256 fingerprint with [`TlsConfig::pin_sha256`],and certificate checks are only skipped when
+//! [`TlsConfig::danger_accept_invalid_certs`] is set.
+//!
+//! ## Example
+//! ```no_run
+//! use metasploit::client::Client;
+//! use metasploit::tls::TlsConfig;
+//! # fn main() -> Result<(),metasploit::error::Error> {
+//! let tls=TlsConfig::new().add_ca_file("/etc/msf/ca.pem")?;
+//! let client=Client::try_new_with_tls("127.0.0.1",55552,"msf","password",tls)?;
+//! # Ok(())
+//! # }
+//! ```
+use crate::error::Error as E;
+use rustls::client::{ServerCertVerified,ServerCertVerifier,WebPkiVerifier};
+use rustls::{Certificate,ClientConfig,RootCertStore,ServerName};
+use sha2::{Digest,Sha256};
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+#[derive(Debug,Clone)]
+pub struct TlsConfig {
+    system_roots:bool,
+    ca:Vec<Certificate>,
+    pin:Option<[u8;32]>,
+    accept_invalid:bool,
+}
+
+impl Default for TlsConfig {
+    fn default() -> Self {
+        TlsConfig {
+            system_roots:true,
+            ca:Vec::new(),
+            pin:None,
+            accept_invalid:false,
+        }
+    }
+}
+
+impl TlsConfig {
+    /// Verifies the server against the system's root certificates.
+    pub fn new() -> Self {
+        TlsConfig::default()
+    }
+    /// Whether the system's root certificates are trusted.Turn it off to only trust the CAs added to this config.
+    pub fn system_roots(mut self,enabled:bool) -> Self {
+        self.system_roots=enabled;
+        self
+    }
+    /// Trusts every certificate in a PEM bundle.
+    pub fn add_ca_pem(mut self,pem:&[u8]) -> Result<Self,E> {
+        let certs=rustls_pemfile::certs(&mut &pem[..]).map_err(|e| E::TlsError{message:"invalid PEM bundle".to_string(),source:Some(Box::new(e))})?;
+        if certs.is_empty() {
+            return Err(E::TlsError{message:"no certificate found in PEM bundle".to_string(),source:None});
+        }
+        let mut roots=RootCertStore::empty();
+        for cert in certs {
+            let cert=Certificate(cert);
+            roots.add(&cert).map_err(|e| E::TlsError{message:"invalid CA certificate".to_string(),source:Some(Box::new(e))})?;
+            self.ca.push(cert);
+        }
+        Ok(self)
+    }
+    /// Trusts every certificate in the PEM file at `path`.
+    pub fn add_ca_file<P:AsRef<Path>>(self,path:P) -> Result<Self,E> {
+        let pem=fs::read(path.as_ref()).map_err(|e| E::TlsError{message:format!("couldn't read {}",path.as_ref().display()),source:Some(Box::new(e))})?;
+        self.add_ca_pem(&pem)
+    }
+    /// Only accepts a server whose certificate has this SHA-256 fingerprint.
+    ///
+    /// The fingerprint is given in hex,with or without `:` separators (as printed by
+    /// `openssl x509 -noout -fingerprint -sha256`).A pinned certificate is accepted even when it is self-signed or
+    /// does not chain to a trusted root,since the pin is a stronger check than the chain.
+    pub fn pin_sha256(mut self,fingerprint:&str) -> Result<Self,E> {
+        let hex:String=fingerprint.chars().filter(|c| *c!=':').collect();
+        let invalid=|| E::TlsError{message:format!("invalid SHA-256 fingerprint: {}",fingerprint),source:None};
+        if hex.len()!=64 || !hex.is_ascii() {
+            return Err(invalid());
+        }
+        let mut pin=[0u8;32];
+        for (i,byte) in pin.iter_mut().enumerate() {
+            *byte=u8::from_str_radix(&hex[i*2..i*2+2],16).map_err(|_| invalid())?;
+        }
+        self.pin=Some(pin);
+        Ok(self)
+    }
+    /// Accepts any server certificate,including expired,self-signed and mismatched ones.
+    ///
+    /// This makes the connection open to interception,so only use it against a server you control on a network you
+    /// trust.A pinned fingerprint still takes precedence over this setting.
+    pub fn danger_accept_invalid_certs(mut self,accept:bool) -> Self {
+        self.accept_invalid=accept;
+        self
+    }
+    pub(crate) fn client_config(&self) -> ClientConfig {
+        let builder=ClientConfig::builder().with_safe_defaults();
+        let verifier:Arc<dyn ServerCertVerifier>=match self.pin {
+            Some(pin) => Arc::new(Pinned(pin)),
+            None if self.accept_invalid => Arc::new(AcceptAny),
+            None => Arc::new(WebPkiVerifier::new(self.roots(),None)),
+        };
+        builder.with_custom_certificate_verifier(verifier).with_no_client_auth()
+    }
+    fn roots(&self) -> RootCertStore {
+        let mut roots=RootCertStore::empty();
+        if self.system_roots {
+            if let Ok(certs)=rustls_native_certs::load_native_certs() {
+                for cert in certs {
+                    let _=roots.add(&Certificate(cert.0));
+                }
+            }
+        }
+        for cert in &self.ca {
+            let _=roots.add(cert);
+        }
+        roots
+    }
+}
+
+struct Pinned([u8;32]);
+
+impl ServerCertVerifier for Pinned {
+    fn verify_server_cert(&self,end_entity:&Certificate,_intermediates:&[Certificate],_server_name:&ServerName,_scts:&mut dyn Iterator<Item=&[u8]>,_ocsp_response:&[u8],_now:SystemTime) -> Result<ServerCertVerified,rustls::Error> {
+        if Sha256::digest(&end_entity.0).as_slice()==self.0 {
+            Ok(ServerCertVerified::assertion())
+        } else {
+            Err(rustls::Error::General("server certificate does not match the pinned fingerprint".to_string()))
+        }
+    }
+}
+
+struct AcceptAny;
+
+impl ServerCertVerifier for AcceptAny {
+    fn verify_server_cert(&self,_end_entity:&Certificate,_intermediates:&[Certificate],_server_name:&ServerName,_scts:&mut dyn Iterator<Item=&[u8]>,_ocsp_response:&[u8],_now:SystemTime) -> Result<ServerCertVerified,rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::TryFrom;
+
+    const FINGERPRINT:&str="3ea58e0c9f1b22d4675a1e0b8c4f2d9a7be61c05d3f8a4e2917b6c0d5e8f1a7a";
+
+    fn verify(verifier:&dyn ServerCertVerifier,cert:&[u8]) -> Result<ServerCertVerified,rustls::Error> {
+        let name=ServerName::try_from("msfrpcd.local").unwrap();
+        verifier.verify_server_cert(&Certificate(cert.to_vec()),&[],&name,&mut std::iter::empty(),&[],SystemTime::now())
+    }
+
+    fn pin(fingerprint:&str) -> Result<[u8;32],E> {
+        TlsConfig::new().pin_sha256(fingerprint).map(|tls| tls.pin.unwrap())
+    }
+
+    #[test]
+    fn pin_sha256_formats() {
+        let plain=pin(FINGERPRINT).unwrap();
+        assert_eq!(plain[..4],[0x3e,0xa5,0x8e,0x0c]);
+        assert_eq!(plain[31],0x7a);
+        let colons:Vec<String>=FINGERPRINT.as_bytes().chunks(2).map(|pair| String::from_utf8(pair.to_vec()).unwrap()).collect();
+        assert_eq!(pin(&colons.join(":").to_uppercase()).unwrap(),plain);
+        let mixed:String=FINGERPRINT.chars().enumerate().map(|(i,c)| if i%3==0 {c.to_ascii_uppercase()} else {c}).collect();
+        assert_eq!(pin(&mixed).unwrap(),plain);
+    }
+
+    #[test]
+    fn pin_sha256_rejects_invalid() {
+        for fingerprint in [&FINGERPRINT[..62],&FINGERPRINT[2..],"",&format!("{}00",FINGERPRINT)] {
+            assert!(matches!(pin(fingerprint),Err(E::TlsError{..})),"{:?}",fingerprint);
+        }
+        let not_hex=format!("zz{}",&FINGERPRINT[2..]);
+        assert!(matches!(pin(&not_hex),Err(E::TlsError{..})));
+        let not_ascii=format!("é{}",&FINGERPRINT[2..]);
+        assert!(matches!(pin(&not_ascii),Err(E::TlsError{..})));
+    }
+
+    #[test]
+    fn pinned_verifier() {
+        let cert=b"self-signed msfrpcd certificate";
+        let pinned=Pinned(Sha256::digest(cert).into());
+        assert!(verify(&pinned,cert).is_ok());
+        assert!(verify(&pinned,b"another certificate").is_err());
+        assert!(verify(&Pinned(pin(FINGERPRINT).unwrap()),cert).is_err());
+    }
+
+    #[test]
+    fn accept_any_verifier() {
+        assert!(verify(&AcceptAny,b"self-signed msfrpcd certificate").is_ok());
+        assert!(verify(&AcceptAny,b"").is_ok());
+    }
+
+    #[test]
+    fn invalid_ca() {
+        assert!(matches!(TlsConfig::new().add_ca_pem(b"not a certificate"),Err(E::TlsError{..})));
+        let garbage=b"-----BEGIN CERTIFICATE-----\nbm90IGEgY2VydGlmaWNhdGU=\n-----END CERTIFICATE-----\n";
+        assert!(matches!(TlsConfig::new().add_ca_pem(garbage),Err(E::TlsError{..})));
+        match TlsConfig::new().add_ca_file("/nonexistent/msf/ca.pem") {
+            Err(E::TlsError{message,source:Some(source)}) => {
+                assert_eq!(message,"couldn't read /nonexistent/msf/ca.pem");
+                assert_eq!(source.downcast_ref::<std::io::Error>().map(std::io::Error::kind),Some(std::io::ErrorKind::NotFound));
+            },
+            other => panic!("expected a TlsError,got {:?}",other.map(|_| ())),
+        }
+    }
+}
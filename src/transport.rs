@@ -0,0 +1,317 @@
+//! How encoded calls reach the server.
+//!
+//! Every call is serialized to a msgpack array (`[method,token,params...]`) and handed to the client's
+//! [`Transport`],which returns the msgpack encoded reply.[`HttpTransport`] talks to msfrpcd (or the JSON-RPC web
+//! service) and is used unless [`ClientBuilder::transport`](crate::client::ClientBuilder::transport) sets another one,
+//! e.g. a [`MemoryTransport`] scripted with replies in unit tests,or a wrapper around [`HttpTransport`] adding
+//! logging or metrics.
+//!
+//! ## Example
+//! ```
+//! use metasploit::client::Client;
+//! use metasploit::transport::MemoryTransport;
+//! use metasploit::value::Value;
+//! # fn main() -> Result<(),metasploit::error::Error> {
+//! let mock=MemoryTransport::new();
+//! mock.reply("job.list",Value::Map(vec![(Value::from("0"),Value::from("Exploit: multi/handler"))]));
+//! let client=Client::builder().transport(mock.clone()).token("TEST")?;
+//! let jobs:Value=client.call("job.list",vec![])?;
+//! assert_eq!(mock.requests()[0],vec![Value::from("job.list"),Value::from("TEST")]);
+//! # Ok(())
+//! # }
+//! ```
+use crate::client::Protocol;
+use crate::error::{ConnectionError as conerr,Error as E};
+use crate::jsonrpc;
+use crate::retry::RetryPolicy;
+use crate::tls::TlsConfig;
+use crate::value::Value;
+use bytes::Bytes;
+use reqwest::{StatusCode,header};
+use std::collections::{HashMap,VecDeque};
+use std::fmt;
+use std::sync::{Arc,Mutex,OnceLock};
+use std::time::Duration;
+#[cfg(feature="async")]
+use std::future::Future;
+#[cfg(feature="async")]
+use std::pin::Pin;
+
+/// The future returned by [`Transport::send_async`].
+#[cfg(feature="async")]
+pub type BoxFuture<'a,T>=Pin<Box<dyn Future<Output=T>+Send+'a>>;
+
+/// Sends an encoded call and returns the encoded reply.
+///
+/// A transport only moves bytes: logging in again on an expired token and decoding the reply (including msfrpcd's
+/// error maps) are done by the client,so a transport returns an error only when no reply could be obtained.
+pub trait Transport:fmt::Debug+Send+Sync {
+    fn send(&self,body:Vec<u8>) -> Result<Vec<u8>,E>;
+    /// Async version of [`Transport::send`].The default calls `send` on the current thread,which is only
+    /// suitable for transports that don't block.
+    #[cfg(feature="async")]
+    fn send_async(&self,body:Vec<u8>) -> BoxFuture<'_,Result<Vec<u8>,E>> {
+        Box::pin(std::future::ready(self.send(body)))
+    }
+}
+
+/// Settings applied to the HTTP clients,collected by [`ClientBuilder`](crate::client::ClientBuilder).
+#[derive(Debug,Clone,Default)]
+pub(crate) struct Settings {
+    pub tls:TlsConfig,
+    pub connect_timeout:Option<Duration>,
+    pub timeout:Option<Duration>,
+    pub user_agent:Option<String>,
+    pub headers:header::HeaderMap,
+    pub retry:Option<RetryPolicy>,
+    pub proxy:Option<reqwest::Proxy>,
+    pub protocol:Protocol,
+}
+
+impl Settings {
+    fn headers(&self) -> header::HeaderMap {
+        let mut header=self.headers.clone();
+        let content_type=match self.protocol {
+            Protocol::MsgPack => "binary/message-pack",
+            Protocol::JsonRpc => "application/json",
+        };
+        header.insert(header::CONTENT_TYPE,header::HeaderValue::from_static(content_type));
+        header
+    }
+}
+
+/// Builds a blocking or async reqwest client with the [`Settings`].The two builders have the same methods but no
+/// common trait.
+macro_rules! build {
+    ($client:path,$settings:expr) => {{
+        let settings=&$settings;
+        let mut builder=<$client>::builder()
+            .default_headers(settings.headers())
+            .use_preconfigured_tls(settings.tls.client_config());
+        if let Some(timeout)=settings.connect_timeout {
+            builder=builder.connect_timeout(timeout);
+        }
+        if let Some(timeout)=settings.timeout {
+            builder=builder.timeout(timeout);
+        }
+        if let Some(agent)=&settings.user_agent {
+            builder=builder.user_agent(agent);
+        }
+        if let Some(proxy)=&settings.proxy {
+            builder=builder.proxy(proxy.clone());
+        }
+        builder.build()
+    }};
+}
+
+/// A call translated to JSON-RPC,and its bearer token.
+type Json=(Bytes,Option<String>);
+
+/// The default transport,posting calls to the RPC endpoint with reqwest.
+///
+/// It is built by the [`ClientBuilder`](crate::client::ClientBuilder) (see
+/// [`ClientBuilder::http_transport`](crate::client::ClientBuilder::http_transport)) and applies its TLS,timeout,
+/// proxy,retry and protocol settings.
+///
+/// The HTTP clients are built on first use and then reused,so consecutive calls go over the same keep-alive
+/// connection instead of paying for a new connection pool (and TLS handshake) every time.
+/// reqwest clients are reference counted internally,so cloning this only bumps the `Arc`s.
+#[derive(Debug,Clone)]
+pub struct HttpTransport {
+    url:String,
+    settings:Arc<Settings>,
+    blocking:Arc<OnceLock<reqwest::blocking::Client>>,
+    #[cfg(feature="async")]
+    nonblocking:Arc<OnceLock<reqwest::Client>>,
+}
+
+impl HttpTransport {
+    pub(crate) fn new(url:String,settings:Settings) -> Self {
+        HttpTransport {
+            url,
+            settings:Arc::new(settings),
+            blocking:Arc::default(),
+            #[cfg(feature="async")]
+            nonblocking:Arc::default(),
+        }
+    }
+    /// The URL calls are posted to.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+    fn blocking(&self) -> Result<&reqwest::blocking::Client,conerr> {
+        if let Some(client)=self.blocking.get() {
+            return Ok(client);
+        }
+        let client=build!(reqwest::blocking::Client,self.settings)?;
+        Ok(self.blocking.get_or_init(|| client))
+    }
+    #[cfg(feature="async")]
+    fn nonblocking(&self) -> Result<&reqwest::Client,conerr> {
+        if let Some(client)=self.nonblocking.get() {
+            return Ok(client);
+        }
+        let client=build!(reqwest::Client,self.settings)?;
+        Ok(self.nonblocking.get_or_init(|| client))
+    }
+    /// The JSON-RPC request and bearer token for a call,when the transport speaks JSON-RPC.
+    fn json(&self,body:&[u8]) -> Result<Option<Json>,E> {
+        if self.settings.protocol!=Protocol::JsonRpc {
+            return Ok(None);
+        }
+        let (json,token)=jsonrpc::request(body)?;
+        Ok(Some((Bytes::from(json),token)))
+    }
+    /// Sends one call,retrying it as allowed by the [`RetryPolicy`].`buf` only holds the reply to the last attempt.
+    fn exchange(&self,body:Bytes,buf:&mut Vec<u8>) -> Result<StatusCode,E> {
+        let json=self.json(&body)?;
+        let mut attempt=1;
+        loop {
+            buf.clear();
+            let result=self.post(body.clone(),json.as_ref(),buf);
+            match self.settings.retry.as_ref().and_then(|policy| policy.retry(attempt,&result,&body)) {
+                Some(delay) => std::thread::sleep(delay),
+                None => return Ok(result?),
+            }
+            attempt+=1;
+        }
+    }
+    #[cfg(feature="async")]
+    async fn exchange_async(&self,body:Bytes,buf:&mut Vec<u8>) -> Result<StatusCode,E> {
+        let json=self.json(&body)?;
+        let mut attempt=1;
+        loop {
+            buf.clear();
+            let result=self.post_async(body.clone(),json.as_ref(),buf).await;
+            match self.settings.retry.as_ref().and_then(|policy| policy.retry(attempt,&result,&body)) {
+                Some(delay) => tokio::time::sleep(delay).await,
+                None => return Ok(result?),
+            }
+            attempt+=1;
+        }
+    }
+    fn post(&self,body:Bytes,json:Option<&Json>,buf:&mut Vec<u8>) -> Result<StatusCode,conerr> {
+        let http=self.blocking()?;
+        let request=match json {
+            Some((json,Some(token))) => http.post(&self.url).body(json.clone()).bearer_auth(token),
+            Some((json,None)) => http.post(&self.url).body(json.clone()),
+            None => http.post(&self.url).body(body),
+        };
+        let mut reader=request.send()?;
+        let status=reader.status();
+        // A 407 comes from the proxy rather than msfrpcd,so there is no reply to decode.
+        if status==StatusCode::PROXY_AUTHENTICATION_REQUIRED {
+            return Err(reader.error_for_status().unwrap_err());
+        }
+        match json {
+            Some(_) => buf.extend_from_slice(&jsonrpc::response(&reader.bytes()?)),
+            None => {
+                reader.copy_to(buf)?;
+            },
+        }
+        Ok(status)
+    }
+    #[cfg(feature="async")]
+    async fn post_async(&self,body:Bytes,json:Option<&Json>,buf:&mut Vec<u8>) -> Result<StatusCode,conerr> {
+        let http=self.nonblocking()?;
+        let request=match json {
+            Some((json,Some(token))) => http.post(&self.url).body(json.clone()).bearer_auth(token),
+            Some((json,None)) => http.post(&self.url).body(json.clone()),
+            None => http.post(&self.url).body(body),
+        };
+        let reader=request.send().await?;
+        let status=reader.status();
+        // A 407 comes from the proxy rather than msfrpcd,so there is no reply to decode.
+        if status==StatusCode::PROXY_AUTHENTICATION_REQUIRED {
+            return Err(reader.error_for_status().unwrap_err());
+        }
+        match json {
+            Some(_) => buf.extend_from_slice(&jsonrpc::response(&reader.bytes().await?)),
+            None => buf.extend_from_slice(&reader.bytes().await?),
+        }
+        Ok(status)
+    }
+}
+
+impl Transport for HttpTransport {
+    fn send(&self,body:Vec<u8>) -> Result<Vec<u8>,E> {
+        let mut buf=vec![];
+        self.exchange(Bytes::from(body),&mut buf)?;
+        Ok(buf)
+    }
+    #[cfg(feature="async")]
+    fn send_async(&self,body:Vec<u8>) -> BoxFuture<'_,Result<Vec<u8>,E>> {
+        Box::pin(async move {
+            let mut buf=vec![];
+            self.exchange_async(Bytes::from(body),&mut buf).await?;
+            Ok(buf)
+        })
+    }
+}
+
+/// A transport answering from replies scripted per method,for tests.
+///
+/// Replies queued for a method are returned in order,one per call.A call with no reply left fails with
+/// `TransportError`.Clones share the same replies and recorded calls,so keep one to inspect what the client sent.
+#[derive(Debug,Clone,Default)]
+pub struct MemoryTransport {
+    state:Arc<Mutex<Memory>>,
+}
+
+#[derive(Debug,Default)]
+struct Memory {
+    replies:HashMap<String,VecDeque<Vec<u8>>>,
+    requests:Vec<Vec<Value>>,
+}
+
+impl MemoryTransport {
+    pub fn new() -> Self {
+        MemoryTransport::default()
+    }
+    /// Queues the reply to the next call of `method`.
+    pub fn reply(&self,method:&str,reply:Value) -> &Self {
+        let mut body=Vec::new();
+        rmpv::encode::write_value(&mut body,&reply).unwrap();
+        self.reply_raw(method,body)
+    }
+    /// Queues the error map msfrpcd sends when `method` raises,e.g. `(401,"Invalid Authentication Token")`.
+    pub fn reply_error(&self,method:&str,code:i64,message:&str) -> &Self {
+        let field=|name:&str,value:Value| (Value::from(name),value);
+        self.reply(method,Value::Map(vec![
+            field("error",Value::from(true)),
+            field("error_class",Value::from("Msf::RPC::Exception")),
+            field("error_string",Value::from(message)),
+            field("error_message",Value::from(message)),
+            field("error_backtrace",Value::Array(vec![])),
+            field("error_code",Value::from(code)),
+        ]))
+    }
+    /// Queues an already encoded reply,e.g. one captured from a real server.
+    pub fn reply_raw(&self,method:&str,reply:Vec<u8>) -> &Self {
+        self.lock().replies.entry(method.to_string()).or_default().push_back(reply);
+        self
+    }
+    /// The calls received so far,each as `[method,token,params...]`.
+    pub fn requests(&self) -> Vec<Vec<Value>> {
+        self.lock().requests.clone()
+    }
+    fn lock(&self) -> std::sync::MutexGuard<'_,Memory> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Transport for MemoryTransport {
+    fn send(&self,body:Vec<u8>) -> Result<Vec<u8>,E> {
+        let call=match rmpv::decode::read_value(&mut body.as_slice()) {
+            Ok(Value::Array(call)) if !call.is_empty() => call,
+            _ => return Err(E::TransportError("request is not an RPC call".into())),
+        };
+        let method=call[0].as_str().unwrap_or_default().to_string();
+        let mut memory=self.lock();
+        memory.requests.push(call);
+        match memory.replies.get_mut(&method).and_then(|replies| replies.pop_front()) {
+            Some(reply) => Ok(reply),
+            None => Err(E::TransportError(format!("no reply scripted for {}",method).into())),
+        }
+    }
+}
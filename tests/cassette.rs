@@ -0,0 +1,87 @@
+//! Recording a session against the mock msfrpcd and replaying it offline.
+use metasploit::cassette::{self,REDACTED,RecordingTransport,ReplayTransport};
+use metasploit::client::Client;
+use metasploit::error::Error;
+use metasploit::mock::MockServer;
+use metasploit::msf::{auth,console,sessions};
+use metasploit::value::Value;
+use std::path::PathBuf;
+
+#[cfg(not(feature="async"))]
+fn run<T>(call:T) -> T {
+    call
+}
+#[cfg(feature="async")]
+fn run<F:std::future::Future>(call:F) -> F::Output {
+    static RUNTIME:std::sync::OnceLock<tokio::runtime::Runtime>=std::sync::OnceLock::new();
+    RUNTIME.get_or_init(|| tokio::runtime::Runtime::new().unwrap()).block_on(call)
+}
+
+fn path(name:&str) -> PathBuf {
+    std::env::temp_dir().join(format!("rust-metasploit-{}-{}.cassette",name,std::process::id()))
+}
+
+#[test]
+fn record_and_replay() {
+    let server=MockServer::start();
+    server.add_session("meterpreter");
+    let file=path("replay");
+    let builder=Client::builder().url(server.url());
+    let recorder=RecordingTransport::create(builder.http_transport().unwrap(),&file).unwrap();
+    let client=builder.transport(recorder).login("msf","password").unwrap();
+    let token=client.gettoken();
+    let live:Value=run(sessions::list(client.clone())).unwrap();
+    let created:Value=run(console::create(client)).unwrap();
+    drop(server);
+
+    let recorded=cassette::load(&file).unwrap();
+    let methods:Vec<&str>=recorded.iter().map(|call| call.method.as_str()).collect();
+    assert_eq!(methods,["auth.login","session.list","console.create"]);
+    assert_eq!(recorded[0].params,[Value::from("msf"),Value::from(REDACTED)]);
+    assert_eq!(recorded[1].params,[Value::from(REDACTED)]);
+    let text=std::fs::read_to_string(&file).unwrap();
+    assert!(!text.contains("password") && !text.contains(&token));
+
+    let replay=ReplayTransport::open(&file).unwrap();
+    let client=Client::builder().transport(replay.clone()).login("msf","password").unwrap();
+    assert_eq!(client.gettoken(),REDACTED);
+    let replayed:Value=run(sessions::list(client.clone())).unwrap();
+    assert_eq!(replayed,live);
+    match run(console::list::<Value>(client.clone())) {
+        Err(Error::TransportError(e)) => assert_eq!(e.to_string(),"cassette expected a call to console.create,got console.list"),
+        other => panic!("expected a TransportError,got {:?}",other),
+    }
+    let replayed:Value=run(console::create(client)).unwrap();
+    assert_eq!(replayed,created);
+    assert_eq!(replay.remaining(),0);
+    std::fs::remove_file(&file).unwrap();
+}
+
+#[test]
+fn tokens_are_redacted() {
+    let server=MockServer::start();
+    let file=path("tokens");
+    let builder=Client::builder().url(server.url());
+    let recorder=RecordingTransport::create(builder.http_transport().unwrap(),&file).unwrap();
+    let client=builder.transport(recorder).login("msf","password").unwrap();
+    let generated:Value=run(auth::generate_token(client.clone())).unwrap();
+    let _:Value=run(auth::add_token(client.clone(),"0123456789abcdef0123456789abcdef")).unwrap();
+    let listed:Value=run(auth::list_token(client.clone())).unwrap();
+    drop(server);
+
+    let mut tokens:Vec<String>=listed["tokens"].as_array().unwrap().iter().map(|token| token.as_str().unwrap().to_string()).collect();
+    assert_eq!(tokens.len(),3);
+    assert!(tokens.contains(&client.gettoken()) && tokens.iter().any(|token| Value::from(token.as_str())==generated["token"]));
+    tokens.push("0123456789abcdef0123456789abcdef".to_string());
+
+    let recorded=cassette::load(&file).unwrap();
+    assert_eq!(recorded.last().unwrap().method,"auth.token_list");
+    let reply=rmpv::decode::read_value(&mut recorded.last().unwrap().response.as_slice()).unwrap();
+    assert_eq!(reply["tokens"],Value::Array(vec![Value::from(REDACTED);3]));
+    let text=std::fs::read_to_string(&file).unwrap();
+    for token in &tokens {
+        let hex:String=token.bytes().map(|byte| format!("{:02x}",byte)).collect();
+        assert!(!text.contains(token.as_str()) && !text.contains(&hex),"{} is in the cassette",token);
+    }
+    std::fs::remove_file(&file).unwrap();
+}
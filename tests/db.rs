@@ -0,0 +1,327 @@
+//! The typed database listings,decoded from the replies in `tests/cassettes/db.cassette`.
+//!
+//! That cassette is recorded from the mock msfrpcd through a `RecordingTransport`,with the mock answering each
+//! listing with the reply in [`replies`].Those have the layout msfrpcd sends,including the `nil` fields and comma
+//! separated references the mock's own records never have.Re-record it after changing them with
+//! `cargo test --features mock --test db -- --ignored`.
+//!
+//! The `*_paged` listings are served copies of the same records,a page at a time,by a `MemoryTransport`.
+use metasploit::cassette::{self,Interaction,REDACTED,RecordingTransport,ReplayTransport};
+use metasploit::client::Client;
+use metasploit::mock::MockServer;
+use metasploit::msf::db::{self,Pages};
+use metasploit::query::{ClientQuery,HostQuery,ListQuery,NoteQuery,ServiceQuery,VulnQuery};
+use metasploit::response::db::{Cred,Event,Host,Loot,Note,Service,Vuln,WebClient};
+use metasploit::transport::MemoryTransport;
+use metasploit::value::Value;
+use std::path::Path;
+
+const CASSETTE:&str=concat!(env!("CARGO_MANIFEST_DIR"),"/tests/cassettes/db.cassette");
+
+#[cfg(not(feature="async"))]
+fn run<T>(call:T) -> T {
+    call
+}
+#[cfg(feature="async")]
+fn run<F:std::future::Future>(call:F) -> F::Output {
+    static RUNTIME:std::sync::OnceLock<tokio::runtime::Runtime>=std::sync::OnceLock::new();
+    RUNTIME.get_or_init(|| tokio::runtime::Runtime::new().unwrap()).block_on(call)
+}
+
+fn record(fields:&[(&str,Value)]) -> Value {
+    Value::Map(fields.iter().map(|(k,v)| (Value::from(*k),v.clone())).collect())
+}
+
+/// The reply to each listing,as the method,the field holding the records and the records.
+fn replies() -> Vec<(&'static str,&'static str,Vec<Value>)> {
+    vec![
+        ("db.hosts","hosts",vec![
+            record(&[
+                ("created_at",Value::from(1697040000)),("address",Value::from("10.0.0.5")),
+                ("mac",Value::from("00:0c:29:3e:5b:11")),("name",Value::from("DC01")),("state",Value::from("alive")),
+                ("os_name",Value::from("Windows Server 2016")),("os_flavor",Value::from("Standard")),
+                ("os_sp",Value::from("")),("os_lang",Value::Nil),("updated_at",Value::from(1697043600)),
+                ("purpose",Value::from("server")),("info",Value::Nil)
+            ]),
+            record(&[
+                ("created_at",Value::from(1697040100)),("address",Value::from("10.0.0.7")),("mac",Value::Nil),
+                ("name",Value::Nil),("state",Value::from("alive")),("os_name",Value::from("Linux")),
+                ("os_flavor",Value::Nil),("os_sp",Value::Nil),("os_lang",Value::Nil),
+                ("updated_at",Value::from(1697040100)),("purpose",Value::from("device")),("info",Value::Nil)
+            ]),
+        ]),
+        ("db.services","services",vec![
+            record(&[
+                ("host",Value::from("10.0.0.5")),("created_at",Value::from(1697040000)),
+                ("updated_at",Value::from(1697043600)),("port",Value::from(445)),("proto",Value::from("tcp")),
+                ("state",Value::from("open")),("name",Value::from("smb")),
+                ("info",Value::from("Windows Server 2016 Standard 14393 (workgroup:ACME)"))
+            ]),
+            record(&[
+                ("host",Value::from("10.0.0.7")),("created_at",Value::from(1697040100)),
+                ("updated_at",Value::from(1697040100)),("port",Value::from(161)),("proto",Value::from("udp")),
+                ("state",Value::from("open")),("name",Value::Nil),("info",Value::Nil)
+            ]),
+        ]),
+        ("db.vulns","vulns",vec![
+            record(&[
+                ("port",Value::from(445)),("proto",Value::from("tcp")),("time",Value::from(1697043600)),
+                ("host",Value::from("10.0.0.5")),
+                ("name",Value::from("MS17-010 EternalBlue SMB Remote Windows Kernel Pool Corruption")),
+                ("refs",Value::from("CVE-2017-0143,CVE-2017-0144,MSB-MS17-010"))
+            ]),
+            record(&[
+                ("port",Value::Nil),("proto",Value::Nil),("time",Value::from(1697043700)),
+                ("host",Value::from("10.0.0.7")),("name",Value::from("Default SNMP community")),
+                ("refs",Value::from(""))
+            ]),
+        ]),
+        ("db.notes","notes",vec![
+            record(&[
+                ("time",Value::from(1697043600)),("host",Value::from("10.0.0.5")),("service",Value::from("smb")),
+                ("type",Value::from("smb.fingerprint")),
+                ("data",Value::from("{:native_os=>\"Windows Server 2016 Standard 14393\"}"))
+            ]),
+            record(&[
+                ("time",Value::from(1697043650)),("host",Value::from("10.0.0.7")),("service",Value::Nil),
+                ("type",Value::from("host.comments")),("data",Value::Nil)
+            ]),
+        ]),
+        ("db.loots","loots",vec![
+            record(&[
+                ("host",Value::from("10.0.0.5")),("service",Value::from("smb")),("ltype",Value::from("windows.hashes")),
+                ("ctype",Value::from("text/plain")),
+                ("data",Value::from("Administrator:500:aad3b435b51404eeaad3b435b51404ee:31d6cfe0d16ae931b73c59d7e0c089c0:::")),
+                ("created_at",Value::from(1697043800)),("updated_at",Value::from(1697043800)),
+                ("name",Value::from("hashes.txt")),("info",Value::from("Windows Hashes"))
+            ]),
+        ]),
+        ("db.creds","creds",vec![
+            record(&[
+                ("host",Value::from("10.0.0.5")),("updated_at",Value::from(1697043800)),("port",Value::from(445)),
+                ("proto",Value::from("tcp")),("sname",Value::from("smb")),("type",Value::from("password")),
+                ("user",Value::from("Administrator")),("pass",Value::from("Passw0rd!")),("active",Value::from(true))
+            ]),
+        ]),
+        ("db.events","events",vec![
+            record(&[
+                ("host",Value::from("10.0.0.5")),("created_at",Value::from(1697043600)),
+                ("updated_at",Value::from(1697043600)),("name",Value::from("module_run")),
+                ("critical",Value::from(false)),("username",Value::from("msf")),
+                ("info",Value::from("{:module_name=>\"exploit/windows/smb/ms17_010_eternalblue\"}"))
+            ]),
+            record(&[
+                ("host",Value::Nil),("created_at",Value::from(1697043500)),("updated_at",Value::from(1697043500)),
+                ("name",Value::from("ui_command")),("critical",Value::Nil),("username",Value::from("msf")),
+                ("info",Value::from("{:command=>\"db_nmap -sV 10.0.0.0/24\"}"))
+            ]),
+        ]),
+        ("db.clients","clients",vec![
+            record(&[
+                ("host",Value::from("10.0.0.9")),
+                ("ua_string",Value::from("Mozilla/5.0 (Windows NT 10.0; Win64; x64; rv:109.0) Gecko/20100101 Firefox/118.0")),
+                ("ua_name",Value::from("Firefox")),("ua_ver",Value::from("118.0")),
+                ("created_at",Value::from(1697044000)),("updated_at",Value::from(1697044000))
+            ]),
+        ]),
+    ]
+}
+
+/// Records every listing,in the order `typed_listings` replays them,into a cassette at `path`.
+fn record_cassette_to(path:&Path) {
+    let server=MockServer::start();
+    for (method,field,records) in replies() {
+        server.respond(method,record(&[(field,Value::Array(records))]));
+    }
+    let builder=Client::builder().url(server.url());
+    let recorder=RecordingTransport::create(builder.http_transport().unwrap(),path).unwrap();
+    let client=builder.transport(recorder).token(&server.client().gettoken()).unwrap();
+    let _:Value=run(db::hosts(client.clone(),HostQuery::new())).unwrap();
+    let _:Value=run(db::services(client.clone(),ServiceQuery::new())).unwrap();
+    let _:Value=run(db::vulns(client.clone(),VulnQuery::new())).unwrap();
+    let _:Value=run(db::notes(client.clone(),NoteQuery::new())).unwrap();
+    let _:Value=run(db::loots(client.clone(),ListQuery::new())).unwrap();
+    let _:Value=run(db::creds(client.clone(),ListQuery::new())).unwrap();
+    let _:Value=run(db::events(client.clone(),ListQuery::new())).unwrap();
+    let _:Value=run(db::clients(client,ClientQuery::new())).unwrap();
+}
+
+#[test]
+#[ignore]
+fn record_cassette() {
+    record_cassette_to(Path::new(CASSETTE));
+}
+
+#[test]
+fn cassette_is_current() {
+    let path=std::env::temp_dir().join(format!("rust-metasploit-db-{}.cassette",std::process::id()));
+    record_cassette_to(&path);
+    let calls=|interactions:Vec<Interaction>| -> Vec<(String,Vec<Value>,Vec<u8>)> {
+        interactions.into_iter().map(|call| (call.method,call.params,call.response)).collect()
+    };
+    assert_eq!(calls(cassette::load(&path).unwrap()),calls(cassette::load(CASSETTE).unwrap()),"re-record the cassette");
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn typed_listings() {
+    for interaction in cassette::load(CASSETTE).unwrap() {
+        assert_eq!(interaction.params,[Value::from(REDACTED),Value::Map(vec![])],"{}",interaction.method);
+    }
+    let replay=ReplayTransport::open(CASSETTE).unwrap();
+    let client=Client::builder().transport(replay.clone()).token("TEST").unwrap();
+
+    let hosts=run(db::hosts_typed(client.clone(),HostQuery::new())).unwrap();
+    assert_eq!(hosts.len(),2);
+    assert_eq!(hosts[0],Host {
+        address:"10.0.0.5".to_string(),
+        mac:"00:0c:29:3e:5b:11".to_string(),
+        name:"DC01".to_string(),
+        state:"alive".to_string(),
+        os_name:"Windows Server 2016".to_string(),
+        os_flavor:"Standard".to_string(),
+        os_sp:String::new(),
+        os_lang:String::new(),
+        purpose:"server".to_string(),
+        info:String::new(),
+        created_at:1697040000,
+        updated_at:1697043600,
+    });
+    assert_eq!((hosts[1].mac.as_str(),hosts[1].name.as_str(),hosts[1].purpose.as_str()),("","","device"));
+
+    let services=run(db::services_typed(client.clone(),ServiceQuery::new())).unwrap();
+    assert_eq!(services[0],Service {
+        host:"10.0.0.5".to_string(),
+        port:445,
+        proto:"tcp".to_string(),
+        state:"open".to_string(),
+        name:"smb".to_string(),
+        info:"Windows Server 2016 Standard 14393 (workgroup:ACME)".to_string(),
+        created_at:1697040000,
+        updated_at:1697043600,
+    });
+    assert_eq!((services[1].port,services[1].proto.as_str(),services[1].name.as_str()),(161,"udp",""));
+
+    let vulns=run(db::vulns_typed(client.clone(),VulnQuery::new())).unwrap();
+    assert_eq!(vulns[0],Vuln {
+        host:"10.0.0.5".to_string(),
+        port:Some(445),
+        proto:Some("tcp".to_string()),
+        name:"MS17-010 EternalBlue SMB Remote Windows Kernel Pool Corruption".to_string(),
+        refs:vec!["CVE-2017-0143".to_string(),"CVE-2017-0144".to_string(),"MSB-MS17-010".to_string()],
+        time:1697043600,
+    });
+    assert_eq!((vulns[1].port,vulns[1].proto.clone(),vulns[1].refs.len()),(None,None,0));
+
+    let notes=run(db::notes_typed(client.clone(),NoteQuery::new())).unwrap();
+    assert_eq!(notes[0],Note {
+        host:"10.0.0.5".to_string(),
+        service:"smb".to_string(),
+        note_type:"smb.fingerprint".to_string(),
+        data:"{:native_os=>\"Windows Server 2016 Standard 14393\"}".to_string(),
+        time:1697043600,
+    });
+    assert_eq!((notes[1].service.as_str(),notes[1].data.as_str()),("",""));
+
+    let loots=run(db::loots_typed(client.clone(),ListQuery::new())).unwrap();
+    assert_eq!(loots,[Loot {
+        host:"10.0.0.5".to_string(),
+        service:"smb".to_string(),
+        ltype:"windows.hashes".to_string(),
+        ctype:"text/plain".to_string(),
+        data:"Administrator:500:aad3b435b51404eeaad3b435b51404ee:31d6cfe0d16ae931b73c59d7e0c089c0:::".to_string(),
+        name:"hashes.txt".to_string(),
+        info:"Windows Hashes".to_string(),
+        created_at:1697043800,
+        updated_at:1697043800,
+    }]);
+
+    let creds=run(db::creds_typed(client.clone(),ListQuery::new())).unwrap();
+    assert_eq!(creds,[Cred {
+        host:"10.0.0.5".to_string(),
+        port:445,
+        proto:"tcp".to_string(),
+        sname:"smb".to_string(),
+        cred_type:"password".to_string(),
+        user:"Administrator".to_string(),
+        pass:"Passw0rd!".to_string(),
+        active:true,
+        updated_at:1697043800,
+    }]);
+
+    let events=run(db::events_typed(client.clone(),ListQuery::new())).unwrap();
+    assert_eq!(events[0],Event {
+        host:"10.0.0.5".to_string(),
+        name:"module_run".to_string(),
+        critical:false,
+        username:"msf".to_string(),
+        info:"{:module_name=>\"exploit/windows/smb/ms17_010_eternalblue\"}".to_string(),
+        created_at:1697043600,
+        updated_at:1697043600,
+    });
+    assert_eq!((events[1].host.as_str(),events[1].name.as_str(),events[1].critical),("","ui_command",false));
+
+    let clients=run(db::clients_typed(client,ClientQuery::new())).unwrap();
+    assert_eq!(clients,[WebClient {
+        host:"10.0.0.9".to_string(),
+        ua_string:"Mozilla/5.0 (Windows NT 10.0; Win64; x64; rv:109.0) Gecko/20100101 Firefox/118.0".to_string(),
+        ua_name:"Firefox".to_string(),
+        ua_ver:"118.0".to_string(),
+        created_at:1697044000,
+        updated_at:1697044000,
+    }]);
+    assert_eq!(replay.remaining(),0);
+}
+
+#[cfg(not(feature="async"))]
+fn collect<T>(pages:Pages<T>) -> Vec<T> {
+    pages.map(Result::unwrap).collect()
+}
+#[cfg(feature="async")]
+fn collect<T>(mut pages:Pages<T>) -> Vec<T> {
+    run(async {
+        let mut records=Vec::new();
+        while let Some(record)=pages.next().await {
+            records.push(record.unwrap());
+        }
+        records
+    })
+}
+
+/// Pages through four copies of the first `method` record in [`replies`],on `10.0.0.1` to `10.0.0.4`,`page_size` at
+/// a time.Returns the hosts of the records listed and the offset and limit of every call made.
+fn paged<T>(method:&str,page_size:u32,list:impl Fn(Client) -> Pages<T>,host:impl Fn(T) -> String) -> (Vec<String>,Vec<[u64;2]>) {
+    let (_,field,records)=replies().into_iter().find(|reply| reply.0==method).unwrap();
+    let records:Vec<Value>=(1..=4).map(|i| match &records[0] {
+        Value::Map(fields) => Value::Map(fields.iter().map(|(k,v)| match k.as_str() {
+            Some("host") => (k.clone(),Value::from(format!("10.0.0.{}",i))),
+            _ => (k.clone(),v.clone()),
+        }).collect()),
+        other => panic!("{} record is not a map: {}",method,other),
+    }).collect();
+    let mock=MemoryTransport::new();
+    for page in records.chunks(page_size as usize) {
+        mock.reply(method,record(&[(field,Value::Array(page.to_vec()))]));
+    }
+    // Only asked for when the last page is full.
+    mock.reply(method,record(&[(field,Value::Array(vec![]))]));
+    let client=Client::builder().transport(mock.clone()).token("TEST").unwrap();
+    let hosts=collect(list(client)).into_iter().map(host).collect();
+    let calls=mock.requests().iter().map(|call| [call[2]["offset"].as_u64().unwrap(),call[2]["limit"].as_u64().unwrap()]).collect();
+    (hosts,calls)
+}
+
+#[test]
+fn paged_listings() {
+    let hosts:Vec<String>=(1..=4).map(|i| format!("10.0.0.{}",i)).collect();
+    for (page_size,calls) in [(2,vec![[0,2],[2,2],[4,2]]),(4,vec![[0,4],[4,4]]),(3,vec![[0,3],[3,3]]),(5,vec![[0,5]])].iter() {
+        let expected=(hosts.clone(),calls.clone());
+        let page_size=*page_size;
+        let check=|name:&str,listed:(Vec<String>,Vec<[u64;2]>)| assert_eq!(listed,expected,"{},page size {}",name,page_size);
+        check("notes",paged("db.notes",page_size,|client| db::notes_paged(client,NoteQuery::new(),page_size),|note:Note| note.host));
+        check("clients",paged("db.clients",page_size,|client| db::clients_paged(client,ClientQuery::new(),page_size),|web:WebClient| web.host));
+        check("events",paged("db.events",page_size,|client| db::events_paged(client,ListQuery::new(),page_size),|event:Event| event.host));
+        check("loots",paged("db.loots",page_size,|client| db::loots_paged(client,ListQuery::new(),page_size),|loot:Loot| loot.host));
+        check("creds",paged("db.creds",page_size,|client| db::creds_paged(client,ListQuery::new(),page_size),|cred:Cred| cred.host));
+    }
+}
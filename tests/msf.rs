@@ -0,0 +1,715 @@
+//! Every function in `msf` against the mock msfrpcd.The same tests run the blocking and the async functions.
+use metasploit::client::Client;
+use metasploit::error::{Error,MsfError,MsfErrorKind};
+use metasploit::mock::MockServer;
+use metasploit::query::{ClientQuery,HostQuery,ListQuery,NoteQuery,ServiceQuery,VulnQuery};
+use metasploit::response::core::{ModuleStats,VersionInfo};
+use metasploit::response::modules::{ModuleReference,ModuleTarget,OptionType};
+use metasploit::response::sessions::SessionType;
+use metasploit::msf::{auth,console,core,db,jobs,modules,plugins,sessions};
+use metasploit::msf::payload::PayloadBuilder;
+use metasploit::value::Value;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+#[derive(Deserialize,Debug)]
+struct Res {
+    result:String,
+}
+
+#[cfg(not(feature="async"))]
+fn run<T>(call:T) -> T {
+    call
+}
+#[cfg(feature="async")]
+fn run<F:std::future::Future>(call:F) -> F::Output {
+    static RUNTIME:std::sync::OnceLock<tokio::runtime::Runtime>=std::sync::OnceLock::new();
+    RUNTIME.get_or_init(|| tokio::runtime::Runtime::new().unwrap()).block_on(call)
+}
+
+fn setup() -> (MockServer,Client) {
+    let server=MockServer::start();
+    let client=server.client();
+    (server,client)
+}
+
+fn success(reply:Result<Value,Error>) {
+    assert_eq!(reply.unwrap()["result"],Value::from("success"));
+}
+
+fn msf_error<T:std::fmt::Debug>(reply:Result<T,Error>,message:&str) {
+    match reply {
+        Err(Error::MsfError(e)) => assert_eq!(e.error_message,message),
+        other => panic!("expected the msf error {:?},got {:?}",message,other),
+    }
+}
+
+fn options(pairs:&[(&str,&str)]) -> HashMap<String,String> {
+    pairs.iter().map(|(k,v)| (k.to_string(),v.to_string())).collect()
+}
+
+/// The entry of a map keyed by integers,as `session.list` and `core.thread_list` reply.
+fn entry(value:&Value,key:u32) -> &Value {
+    value.as_map().unwrap().iter().find(|(k,_)| *k==Value::from(key)).map(|(_,v)| v).unwrap()
+}
+
+fn strings(value:&Value) -> Vec<&str> {
+    value.as_array().unwrap().iter().map(|item| item.as_str().unwrap()).collect()
+}
+
+#[test]
+fn auth_tokens() {
+    let (_server,client)=setup();
+    success(run(auth::add_token(client.clone(),"PERMANENT")));
+    let generated:Value=run(auth::generate_token(client.clone())).unwrap();
+    let generated=generated["token"].as_str().unwrap().to_string();
+    let list:Value=run(auth::list_token(client.clone())).unwrap();
+    let tokens=strings(&list["tokens"]);
+    assert!(tokens.contains(&"PERMANENT") && tokens.contains(&generated.as_str()));
+    success(run(auth::remove_token(client.clone(),&generated)));
+    let list:Value=run(auth::list_token(client.clone())).unwrap();
+    assert!(!strings(&list["tokens"]).contains(&generated.as_str()));
+
+    let permanent=Client::builder().url(client.url.as_str()).token("PERMANENT").unwrap();
+    success(run(auth::logout(client.clone())));
+    msf_error(run(core::version::<Res>(client)),"Invalid Authentication Token");
+    let _:Value=run(core::version(permanent)).unwrap();
+}
+
+#[test]
+fn login_failure() {
+    let server=MockServer::with_credentials("admin","s3cret");
+    match Client::builder().url(server.url()).login("admin","wrong") {
+        Err(Error::AuthError{message,..}) => assert_eq!(message,"Login Failed"),
+        other => panic!("expected a login failure,got {:?}",other),
+    }
+    let client=Client::builder().url(server.url()).login("admin","s3cret").unwrap();
+    assert!(client.gettoken().starts_with("TEMP"));
+}
+
+#[test]
+fn checked_token() {
+    let (_server,client)=setup();
+    success(run(auth::add_token(client.clone(),"PERMANENT")));
+    match Client::builder().url(client.url.as_str()).token_checked("BOGUS") {
+        Err(Error::AuthError{message,source:Some(source)}) => {
+            assert_eq!(message,"Invalid Authentication Token");
+            assert_eq!(source.downcast_ref::<MsfError>().and_then(|e| e.error_code),Some(401));
+        },
+        other => panic!("expected the token to be rejected,got {:?}",other),
+    }
+    let permanent=Client::builder().url(client.url.as_str()).token_checked("PERMANENT").unwrap();
+    assert_eq!(permanent.gettoken(),"PERMANENT");
+}
+
+#[test]
+fn expired_token_is_renewed() {
+    let server=MockServer::start();
+    let client=Client::builder().url(server.url()).reauthenticate(true).login("msf","password").unwrap();
+    let before=client.gettoken();
+    server.expire_tokens();
+    let _:Value=run(core::version(client.clone())).unwrap();
+    assert_ne!(client.gettoken(),before);
+    let logins=server.calls().iter().filter(|call| call[0]==Value::from("auth.login")).count();
+    assert_eq!(logins,2);
+}
+
+#[test]
+fn console_lifecycle() {
+    let (_server,client)=setup();
+    let created:Value=run(console::create(client.clone())).unwrap();
+    let id=created["id"].as_str().unwrap().to_string();
+    let list:Value=run(console::list(client.clone())).unwrap();
+    assert_eq!(list["consoles"][0]["id"],Value::from(id.as_str()));
+
+    let wrote:Value=run(console::write(client.clone(),&id,"version\n")).unwrap();
+    assert_eq!(wrote["wrote"],Value::from(8));
+    let read:Value=run(console::read(client.clone(),&id)).unwrap();
+    assert_eq!(read["data"],Value::from("msf6 > version\n"));
+    let read:Value=run(console::read(client.clone(),&id)).unwrap();
+    assert_eq!(read["data"],Value::from(""));
+
+    let tabs:Value=run(console::tabs(client.clone(),&id,"se")).unwrap();
+    assert_eq!(strings(&tabs["tabs"]),["search","sessions","set","setg"]);
+    success(run(console::detach_session(client.clone(),&id)));
+    success(run(console::kill_session(client.clone(),&id)));
+    success(run(console::destroy(client.clone(),&id)));
+    let destroyed:Res=run(console::destroy(client.clone(),&id)).unwrap();
+    assert_eq!(destroyed.result,"failure");
+    let list:Value=run(console::list(client)).unwrap();
+    assert!(list["consoles"].as_array().unwrap().is_empty());
+}
+
+#[test]
+fn core_functions() {
+    let (server,client)=setup();
+    let version:Value=run(core::version(client.clone())).unwrap();
+    assert_eq!(version["api"],Value::from("1.0"));
+    let stats:Value=run(core::module_status(client.clone())).unwrap();
+    assert_eq!(stats["exploits"],Value::from(3));
+    let _:Value=run(core::add_module(client.clone(),"/opt/modules")).unwrap();
+    let _:Value=run(core::reload_module(client.clone())).unwrap();
+    success(run(core::save(client.clone())));
+    success(run(core::setg(client.clone(),"LHOST","10.0.0.2")));
+    success(run(core::unsetg(client.clone(),"LHOST")));
+    let threads:Value=run(core::list_thread(client.clone())).unwrap();
+    assert_eq!(entry(&threads,0)["name"],Value::from("StreamServerListener"));
+    success(run(core::kill_thread(client.clone(),0)));
+    success(run(core::stop(client)));
+
+    let calls=server.calls();
+    let setg=calls.iter().find(|call| call[0]==Value::from("core.setg")).unwrap();
+    assert_eq!(setg[2..],[Value::from("LHOST"),Value::from("10.0.0.2")]);
+    let kill=calls.iter().find(|call| call[0]==Value::from("core.thread_kill")).unwrap();
+    assert_eq!(kill[2],Value::from(0));
+}
+
+#[test]
+fn typed_core_replies() {
+    let (server,client)=setup();
+    let version=run(core::version_typed(client.clone())).unwrap();
+    assert_eq!(version,VersionInfo{version:"6.3.25-dev".to_string(),ruby:"3.0.5 x86_64-linux 2022-11-24".to_string(),api:"1.0".to_string()});
+    let old=Value::Map(vec![(Value::from("version"),Value::from("4.11.8")),(Value::from("ruby"),Value::from("2.1.5 x86_64-linux 2014-11-13"))]);
+    server.respond("core.version",old);
+    let version=run(core::version_typed(client.clone())).unwrap();
+    assert_eq!((version.version.as_str(),version.api.as_str()),("4.11.8",""));
+    let stats=run(core::module_status_typed(client.clone())).unwrap();
+    assert_eq!(stats,ModuleStats{exploits:3,auxiliary:2,post:2,encoders:2,nops:1,payloads:3,evasion:0});
+    let threads=run(core::list_thread_typed(client)).unwrap();
+    assert_eq!(threads[&0].name,"StreamServerListener");
+    assert!(!threads[&0].critical);
+}
+
+#[test]
+fn jobs_from_executed_modules() {
+    let (_server,client)=setup();
+    let executed:Value=run(modules::execute(client.clone(),"exploit","multi/handler",options(&[("PAYLOAD","cmd/unix/reverse")]))).unwrap();
+    let id=executed["job_id"].as_u64().unwrap().to_string();
+    let list:Value=run(jobs::list(client.clone())).unwrap();
+    assert_eq!(list[id.as_str()],Value::from("Exploit: multi/handler"));
+    let info:Value=run(jobs::info(client.clone(),&id)).unwrap();
+    assert_eq!(info["datastore"]["PAYLOAD"],Value::from("cmd/unix/reverse"));
+    success(run(jobs::stop(client.clone(),&id)));
+    msf_error(run(jobs::stop::<Res>(client.clone(),&id)),"Invalid Job");
+    msf_error(run(jobs::info::<Res>(client,"42")),"Invalid Job");
+}
+
+#[test]
+fn module_lists() {
+    let (_server,client)=setup();
+    let list=modules::list::new(client);
+    let exploits:Value=run(list.exploits()).unwrap();
+    assert!(strings(&exploits["modules"]).contains(&"multi/handler"));
+    let auxiliary:Value=run(list.auxiliary()).unwrap();
+    assert!(strings(&auxiliary["modules"]).contains(&"scanner/portscan/tcp"));
+    let post:Value=run(list.post()).unwrap();
+    assert_eq!(post["modules"].as_array().unwrap().len(),2);
+    let payloads:Value=run(list.payloads()).unwrap();
+    assert!(strings(&payloads["modules"]).contains(&"cmd/unix/reverse"));
+    let encoders:Value=run(list.encoders()).unwrap();
+    assert!(strings(&encoders["modules"]).contains(&"x86/shikata_ga_nai"));
+    let nops:Value=run(list.nops()).unwrap();
+    assert_eq!(strings(&nops["modules"]),["x86/single_byte"]);
+}
+
+#[test]
+fn module_details() {
+    let (server,client)=setup();
+    let info:Value=run(modules::info(client.clone(),"exploit","windows/smb/ms17_010_eternalblue")).unwrap();
+    assert_eq!(info["rank"],Value::from("excellent"));
+    msf_error(run(modules::info::<Res>(client.clone(),"exploit","windows/nothing")),"Invalid Module");
+    let option:Value=run(modules::option(client.clone(),"auxiliary","scanner/smb/smb_version")).unwrap();
+    assert_eq!(option["RPORT"]["default"],Value::from(445));
+    let encoded:Value=run(modules::encoder(client.clone(),"AB","x86/shikata_ga_nai",HashMap::new())).unwrap();
+    assert_eq!(encoded["encoded"],Value::from("\\x41\\x42"));
+    let payload:Value=run(modules::execute(client.clone(),"payload","linux/x64/shell_reverse_tcp",options(&[("LHOST","10.0.0.2")]))).unwrap();
+    assert!(!payload["payload"].as_slice().unwrap().is_empty());
+
+    let session=server.add_session("shell");
+    let compatible=modules::compactible::new("exploit/multi/handler".to_string(),client);
+    let payloads:Value=run(compatible.payload()).unwrap();
+    assert!(strings(&payloads["payloads"]).contains(&"windows/x64/meterpreter/reverse_tcp"));
+    let targeted:Value=run(compatible.target_payloads(0)).unwrap();
+    assert_eq!(targeted["payloads"],payloads["payloads"]);
+    let sessions:Value=run(compatible.sessions()).unwrap();
+    assert_eq!(sessions["sessions"][0],Value::from(session));
+
+    let calls=server.calls();
+    let target=calls.iter().find(|call| call[0]==Value::from("module.target_compatible_payloads")).unwrap();
+    assert_eq!(target[2..],[Value::from("exploit/multi/handler"),Value::from(0)]);
+}
+
+#[test]
+fn typed_module_details() {
+    let (_server,client)=setup();
+    let info=run(modules::info_typed(client.clone(),"exploit","windows/smb/ms17_010_eternalblue")).unwrap();
+    assert_eq!((info.module_type.as_str(),info.rank.as_str(),info.check),("exploit","excellent",true));
+    assert_eq!(info.references,[ModuleReference{kind:"CVE".to_string(),value:"2017-0144".to_string()}]);
+    assert_eq!(info.targets,[ModuleTarget{index:0,name:"Automatic Target".to_string()}]);
+    assert_eq!(info.default_target,Some(0));
+    assert!(info.actions.is_empty());
+
+    let options=run(modules::option_typed(client,"exploit","windows/smb/ms17_010_eternalblue")).unwrap();
+    assert_eq!(options,info.options);
+    let rhosts=&options["RHOSTS"];
+    assert_eq!((rhosts.option_type.clone(),rhosts.required,rhosts.default.clone()),(OptionType::AddressRange,true,None));
+    assert_eq!(options["RPORT"].option_type,OptionType::Port);
+    assert_eq!(options["RPORT"].default,Some(Value::from(445)));
+    assert!(options["VERBOSE"].advanced);
+}
+
+#[test]
+fn payload_builder() {
+    let (server,client)=setup();
+    let formats:Value=run(modules::executable_formats(client.clone())).unwrap();
+    assert!(strings(&formats).contains(&"exe"));
+    let formats:Value=run(modules::transform_formats(client.clone())).unwrap();
+    assert!(strings(&formats).contains(&"python"));
+
+    let builder=PayloadBuilder::new(client.clone(),"windows/x64/meterpreter/reverse_tcp")
+        .option("LHOST","10.0.0.2")
+        .option("LPORT","4444")
+        .format("exe")
+        .encoder("x64/xor")
+        .iterations(3)
+        .bad_chars(b"\x00\x0a\xff")
+        .template("/usr/share/windows-binaries/plink.exe");
+    let before=server.calls().len();
+    let payload=run(builder.generate()).unwrap();
+    assert_eq!(payload,b"\xfc\x48\x83\xe4\xf0\xe8\xcc\x00\x00\x00");
+    let calls=server.calls();
+    assert_eq!(calls.len(),before+1,"a known format is generated in one call");
+    let execute=calls.iter().find(|call| call[0]==Value::from("module.execute")).unwrap();
+    assert_eq!(execute[2..4],[Value::from("payload"),Value::from("windows/x64/meterpreter/reverse_tcp")]);
+    let sent=&execute[4];
+    assert_eq!(sent["LHOST"],Value::from("10.0.0.2"));
+    assert_eq!(sent["Format"],Value::from("exe"));
+    assert_eq!(sent["Encoder"],Value::from("x64/xor"));
+    assert_eq!(sent["Iterations"],Value::from(3));
+    assert_eq!(sent["BadChars"],Value::Binary(vec![0x00,0x0a,0xff]));
+    assert_eq!(sent["Template"],Value::from("/usr/share/windows-binaries/plink.exe"));
+
+    let file=std::env::temp_dir().join(format!("rust-metasploit-payload-{}.py",std::process::id()));
+    run(PayloadBuilder::new(client.clone(),"cmd/unix/reverse").format("python").write_to(&file)).unwrap();
+    assert_eq!(std::fs::read(&file).unwrap(),payload);
+    std::fs::remove_file(&file).unwrap();
+
+    match run(PayloadBuilder::new(client.clone(),"cmd/unix/reverse").format("docx").generate()) {
+        Err(Error::PayloadError{message,..}) => assert_eq!(message,"docx is neither an executable nor a transform format"),
+        other => panic!("expected a PayloadError,got {:?}",other),
+    }
+    msf_error(run(PayloadBuilder::new(client.clone(),"cmd/unix/nothing").generate()),"Invalid Module");
+    server.respond_error("module.execute",500,"failed to generate: LHOST is required");
+    msf_error(run(PayloadBuilder::new(client,"cmd/unix/reverse").format("python").generate()),"failed to generate: LHOST is required");
+}
+
+#[test]
+fn plugin_functions() {
+    let (_server,client)=setup();
+    success(run(plugins::load(client.clone(),"msgrpc",options(&[("Pass","s3cret")]))));
+    let loaded:Value=run(plugins::list(client.clone())).unwrap();
+    assert_eq!(strings(&loaded["plugins"]),["msgrpc"]);
+    success(run(plugins::unload(client.clone(),"msgrpc")));
+    let unloaded:Res=run(plugins::unload(client.clone(),"msgrpc")).unwrap();
+    assert_eq!(unloaded.result,"failure");
+    let loaded:Value=run(plugins::list(client)).unwrap();
+    assert!(loaded["plugins"].as_array().unwrap().is_empty());
+}
+
+#[test]
+fn shell_sessions() {
+    let (server,client)=setup();
+    let id=server.add_session("shell");
+    let list:Value=run(sessions::list(client.clone())).unwrap();
+    assert_eq!(entry(&list,id)["type"],Value::from("shell"));
+    let id=id.to_string();
+
+    server.push_output(id.parse().unwrap(),"uid=0(root)\n",false);
+    let read:Value=run(sessions::shell::read(client.clone(),&id,None)).unwrap();
+    assert_eq!(read["data"],Value::from("uid=0(root)\n"));
+    let wrote:Value=run(sessions::shell::write(client.clone(),&id,"id\n")).unwrap();
+    assert_eq!(wrote["write_count"],Value::from(3));
+    let read:Value=run(sessions::shell::read(client.clone(),&id,Some(0))).unwrap();
+    assert_eq!(read["data"],Value::from("id\n"));
+    success(run(sessions::shell_upgrade(client.clone(),&id,"10.0.0.2",4444)));
+
+    let ring=sessions::ring::new(client.clone(),&id);
+    let _:Value=run(ring.put("whoami\n")).unwrap();
+    let last:Value=run(ring.last()).unwrap();
+    assert_eq!(last["seq"],Value::from(2));
+    success(run(ring.clear()));
+    let last:Value=run(ring.last()).unwrap();
+    assert_eq!(last["seq"],Value::from(0));
+
+    let meterpreter=sessions::meterpreter::new(client.clone(),&id);
+    msf_error(run(meterpreter.read::<Res>()),&format!("Session {} is not a meterpreter session",id));
+    success(run(sessions::stop(client.clone(),&id)));
+    msf_error(run(sessions::stop::<Res>(client,&id)),"Unknown Session ID");
+}
+
+#[test]
+fn typed_sessions() {
+    let (server,client)=setup();
+    let shell=server.add_session("shell");
+    let meterpreter=server.add_session("meterpreter");
+    let list=run(sessions::list_typed(client.clone())).unwrap();
+    assert_eq!(list.keys().copied().collect::<Vec<u32>>(),[shell,meterpreter]);
+    assert_eq!(list[&shell].session_type,SessionType::Shell);
+    let session=&list[&meterpreter];
+    assert_eq!(session.session_type,SessionType::Meterpreter);
+    assert_eq!((session.tunnel_peer.as_str(),session.session_port),("10.0.0.5:49153",445));
+    assert_eq!(session.platform,"windows");
+
+    // A reply from an older server,without most fields and with a session type this crate doesn't know.
+    let old=Value::Map(vec![(Value::from(3),Value::Map(vec![
+        (Value::from("type"),Value::from("vnc-proxy")),
+        (Value::from("tunnel_peer"),Value::from("10.0.0.9:5900")),
+        (Value::from("via_exploit"),Value::from("exploit/multi/handler")),
+    ]))]);
+    server.respond("session.list",old);
+    let list=run(sessions::list_typed(client)).unwrap();
+    assert_eq!(list[&3].session_type,SessionType::Other("vnc-proxy".to_string()));
+    assert_eq!((list[&3].session_host.as_str(),list[&3].session_port),("",0));
+}
+
+#[test]
+fn meterpreter_sessions() {
+    let (server,client)=setup();
+    let id=server.add_session("meterpreter").to_string();
+    let meterpreter=sessions::meterpreter::new(client.clone(),&id);
+    success(run(meterpreter.write("getuid")));
+    success(run(meterpreter.run_single("sysinfo")));
+    let read:Value=run(meterpreter.read()).unwrap();
+    assert_eq!(read["data"],Value::from("getuidsysinfo"));
+    success(run(meterpreter.script("checkvm")));
+    let tabs:Value=run(meterpreter.tabs("he")).unwrap();
+    assert_eq!(strings(&tabs["tabs"]),["help"]);
+    let compatible:Value=run(meterpreter.compactible_modules()).unwrap();
+    assert!(strings(&compatible["modules"]).contains(&"multi/recon/local_exploit_suggester"));
+    success(run(meterpreter.detach_session()));
+    success(run(meterpreter.kill_session()));
+    msf_error(run(sessions::shell::read::<Res>(client,&id,None)),&format!("Session {} is not a shell session",id));
+}
+
+#[test]
+fn db_hosts_and_workspaces() {
+    let (_server,client)=setup();
+    success(run(db::add_workspace(client.clone(),"acme")));
+    success(run(db::set_workspace(client.clone(),"acme")));
+    let current:Value=run(db::current_workspace(client.clone())).unwrap();
+    assert_eq!(current["workspace"],Value::from("acme"));
+    let workspaces:Value=run(db::workspaces(client.clone())).unwrap();
+    assert_eq!(workspaces["workspaces"].as_array().unwrap().len(),2);
+    let workspace:Value=run(db::get_workspace(client.clone(),"acme")).unwrap();
+    assert_eq!(workspace["workspace"][0]["name"],Value::from("acme"));
+
+    success(run(db::report_host(client.clone(),None,"10.0.0.5")));
+    success(run(db::report_host(client.clone(),Some("default".to_string()),"10.0.0.6")));
+    let hosts:Value=run(db::hosts(client.clone(),HostQuery::new())).unwrap();
+    assert_eq!(hosts["hosts"][0]["address"],Value::from("10.0.0.5"));
+    assert_eq!(hosts["hosts"].as_array().unwrap().len(),1);
+    let host:Value=run(db::get_host(client.clone(),Some("default".to_string()),"10.0.0.6")).unwrap();
+    assert_eq!(host["host"][0]["workspace"],Value::from("default"));
+    let deleted:Value=run(db::del_host(client.clone(),None,"10.0.0.5")).unwrap();
+    assert_eq!(strings(&deleted["deleted"]),["10.0.0.5"]);
+    let hosts:Value=run(db::hosts(client.clone(),HostQuery::new())).unwrap();
+    assert!(hosts["hosts"].as_array().unwrap().is_empty());
+
+    success(run(db::del_workspace(client.clone(),"acme")));
+    let current:Value=run(db::current_workspace(client.clone())).unwrap();
+    assert_eq!(current["workspace"],Value::from("default"));
+    msf_error(run(db::get_workspace::<Res>(client,"acme")),"Invalid workspace");
+}
+
+#[test]
+fn db_queries() {
+    let (server,client)=setup();
+    for address in ["10.0.0.1","10.0.0.2","10.0.0.3","10.0.0.4"] {
+        success(run(db::report_host(client.clone(),None,address)));
+    }
+    let hosts=run(db::hosts_typed(client.clone(),HostQuery::new().limit(2).offset(1))).unwrap();
+    let addresses:Vec<&str>=hosts.iter().map(|host| host.address.as_str()).collect();
+    assert_eq!(addresses,["10.0.0.2","10.0.0.3"]);
+    let hosts=run(db::hosts_typed(client.clone(),HostQuery::new().only_up(true).address("10.0.0.1").address("10.0.0.4"))).unwrap();
+    let addresses:Vec<&str>=hosts.iter().map(|host| host.address.as_str()).collect();
+    assert_eq!(addresses,["10.0.0.1","10.0.0.4"]);
+    let sent=server.calls().last().unwrap()[2].clone();
+    assert_eq!(sent["only_up"],Value::from(true));
+    assert_eq!(strings(&sent["addresses"]),["10.0.0.1","10.0.0.4"]);
+
+    let query=ServiceQuery::new().workspace("default").limit(50).offset(100).only_up(true).address("10.0.0.5")
+        .proto("tcp").ports(1..=1024).port(8080).name("smb").name("http");
+    let _:Value=run(db::services(client.clone(),query)).unwrap();
+    let sent=server.calls().last().unwrap()[2].clone();
+    assert_eq!(sent["workspace"],Value::from("default"));
+    assert_eq!(sent["limit"],Value::from(50));
+    assert_eq!(sent["offset"],Value::from(100));
+    assert_eq!(sent["only_up"],Value::from(true));
+    assert_eq!(strings(&sent["addresses"]),["10.0.0.5"]);
+    assert_eq!(sent["proto"],Value::from("tcp"));
+    assert_eq!(sent["ports"],Value::from("1-1024,8080"));
+    assert_eq!(sent["names"],Value::from("smb,http"));
+
+    let _:Value=run(db::notes(client.clone(),NoteQuery::new().note_type("smb.fingerprint").port(445))).unwrap();
+    let sent=server.calls().last().unwrap()[2].clone();
+    assert_eq!(sent["ntype"],Value::from("smb.fingerprint"));
+    assert_eq!(sent["ports"],Value::from("445"));
+    let _:Value=run(db::creds(client,ListQuery::new())).unwrap();
+    assert!(server.calls().last().unwrap()[2].as_map().unwrap().is_empty());
+}
+
+#[test]
+fn db_workspace_handle() {
+    let (server,client)=setup();
+    let acme=run(client.workspace("acme-ext")).unwrap();
+    assert_eq!(acme.name(),"acme-ext");
+    let again=run(client.workspace("acme-ext")).unwrap();
+    let workspaces:Value=run(db::workspaces(client.clone())).unwrap();
+    assert_eq!(workspaces["workspaces"].as_array().unwrap().len(),2);
+
+    success(run(acme.report_host("10.0.0.5")));
+    success(run(db::report_host(client.clone(),None,"10.0.0.6")));
+    let hosts=run(again.hosts_typed(HostQuery::new())).unwrap();
+    assert_eq!(hosts.len(),1);
+    assert_eq!(hosts[0].address,"10.0.0.5");
+    let default=run(db::hosts_typed(client.clone(),HostQuery::new())).unwrap();
+    assert_eq!(default[0].address,"10.0.0.6");
+    let current:Value=run(db::current_workspace(client.clone())).unwrap();
+    assert_eq!(current["workspace"],Value::from("default"));
+
+    success(run(acme.report_service(options(&[("host","10.0.0.5"),("port","445"),("workspace","default")]))));
+    let _=run(acme.services_typed(ServiceQuery::new().workspace("default"))).unwrap();
+    let _=run(acme.creds_typed(ListQuery::new())).unwrap();
+    let calls=server.calls();
+    for method in ["db.report_service","db.services","db.creds"] {
+        let call=calls.iter().find(|call| call[0]==Value::from(method)).unwrap();
+        assert_eq!(call[2]["workspace"],Value::from("acme-ext"));
+    }
+    let deleted:Value=run(acme.del_host("10.0.0.5")).unwrap();
+    assert_eq!(strings(&deleted["deleted"]),["10.0.0.5"]);
+    assert!(run(acme.hosts_typed(HostQuery::new())).unwrap().is_empty());
+}
+
+/// The `[offset,limit]` of every call of `method` after the first `skip` calls.
+fn pages(server:&MockServer,method:&str,skip:usize) -> Vec<[u64;2]> {
+    server.calls()[skip..].iter().filter(|call| call[0]==Value::from(method))
+        .map(|call| [call[2]["offset"].as_u64().unwrap(),call[2]["limit"].as_u64().unwrap()]).collect()
+}
+
+/// Reports a vulnerability on each of `10.0.0.1` to `10.0.0.{count}`.
+fn report_vulns(client:&Client,count:u32) {
+    for i in 1..=count {
+        success(run(db::report_vuln(client.clone(),options(&[("host",&format!("10.0.0.{}",i)),("name","MS17-010")]))));
+    }
+}
+
+#[test]
+#[cfg(not(feature="async"))]
+fn db_pages() {
+    let (server,client)=setup();
+    for i in 1..=5 {
+        success(db::report_host(client.clone(),None,&format!("10.0.0.{}",i)));
+    }
+    let before=server.calls().len();
+    let hosts:Vec<String>=db::hosts_paged(client.clone(),HostQuery::new(),2).map(|host| host.unwrap().address).collect();
+    assert_eq!(hosts,["10.0.0.1","10.0.0.2","10.0.0.3","10.0.0.4","10.0.0.5"]);
+    assert_eq!(pages(&server,"db.hosts",before),[[0,2],[2,2],[4,2]]);
+
+    let before=server.calls().len();
+    let first:Vec<String>=db::hosts_paged(client.clone(),HostQuery::new(),2).take(3).map(|host| host.unwrap().address).collect();
+    assert_eq!(first,["10.0.0.1","10.0.0.2","10.0.0.3"]);
+    assert_eq!(pages(&server,"db.hosts",before),[[0,2],[2,2]]);
+
+    report_vulns(&client,4);
+    for (page_size,expected) in [(2,vec![[0,2],[2,2],[4,2]]),(4,vec![[0,4],[4,4]]),(3,vec![[0,3],[3,3]]),(5,vec![[0,5]])] {
+        let before=server.calls().len();
+        let vulns:Vec<String>=db::vulns_paged(client.clone(),VulnQuery::new(),page_size).map(|vuln| vuln.unwrap().host).collect();
+        assert_eq!(vulns,["10.0.0.1","10.0.0.2","10.0.0.3","10.0.0.4"],"page size {}",page_size);
+        assert_eq!(pages(&server,"db.vulns",before),expected,"page size {}",page_size);
+    }
+    let before=server.calls().len();
+    assert_eq!(db::vulns_paged(client.clone(),VulnQuery::new().address("10.0.0.9"),2).count(),0);
+    assert_eq!(pages(&server,"db.vulns",before),[[0,2]]);
+
+    server.respond_error("db.services",500,"Database not connected");
+    let mut services=db::services_paged(client,ServiceQuery::new(),10);
+    msf_error(services.next().unwrap(),"Database not connected");
+    assert!(services.next().is_none());
+}
+
+#[test]
+#[cfg(feature="async")]
+fn db_pages() {
+    let (server,client)=setup();
+    for i in 1..=5 {
+        success(run(db::report_host(client.clone(),None,&format!("10.0.0.{}",i))));
+    }
+    let before=server.calls().len();
+    let hosts=run(async {
+        let mut pages=db::hosts_paged(client.clone(),HostQuery::new(),2);
+        let mut hosts=Vec::new();
+        while let Some(host)=pages.next().await {
+            hosts.push(host.unwrap().address);
+        }
+        hosts
+    });
+    assert_eq!(hosts,["10.0.0.1","10.0.0.2","10.0.0.3","10.0.0.4","10.0.0.5"]);
+    assert_eq!(pages(&server,"db.hosts",before),[[0,2],[2,2],[4,2]]);
+
+    let before=server.calls().len();
+    let first=run(async {
+        let mut pages=db::hosts_paged(client.clone(),HostQuery::new(),2);
+        let mut hosts=Vec::new();
+        while let Some(host)=pages.next().await {
+            hosts.push(host.unwrap().address);
+            if hosts.len()==3 {
+                break;
+            }
+        }
+        hosts
+    });
+    assert_eq!(first,["10.0.0.1","10.0.0.2","10.0.0.3"]);
+    assert_eq!(pages(&server,"db.hosts",before),[[0,2],[2,2]]);
+
+    report_vulns(&client,4);
+    let vulns=|query:VulnQuery,page_size:u32| run(async {
+        let mut pages=db::vulns_paged(client.clone(),query,page_size);
+        let mut vulns=Vec::new();
+        while let Some(vuln)=pages.next().await {
+            vulns.push(vuln.unwrap().host);
+        }
+        vulns
+    });
+    for (page_size,expected) in [(2,vec![[0,2],[2,2],[4,2]]),(4,vec![[0,4],[4,4]]),(3,vec![[0,3],[3,3]]),(5,vec![[0,5]])] {
+        let before=server.calls().len();
+        assert_eq!(vulns(VulnQuery::new(),page_size),["10.0.0.1","10.0.0.2","10.0.0.3","10.0.0.4"],"page size {}",page_size);
+        assert_eq!(pages(&server,"db.vulns",before),expected,"page size {}",page_size);
+    }
+    let before=server.calls().len();
+    assert!(vulns(VulnQuery::new().address("10.0.0.9"),2).is_empty());
+    assert_eq!(pages(&server,"db.vulns",before),[[0,2]]);
+
+    server.respond_error("db.services",500,"Database not connected");
+    let mut services=db::services_paged(client,ServiceQuery::new(),10);
+    msf_error(run(services.next()).unwrap(),"Database not connected");
+    assert!(run(services.next()).is_none());
+}
+
+#[test]
+fn db_records() {
+    let (server,client)=setup();
+    let host=options(&[("host","10.0.0.5")]);
+    success(run(db::report_service(client.clone(),options(&[("host","10.0.0.5"),("port","445"),("proto","tcp")]))));
+    let services:Value=run(db::services(client.clone(),ServiceQuery::new().address("10.0.0.5"))).unwrap();
+    assert_eq!(services["services"][0]["port"],Value::from(445));
+    let service:Value=run(db::get_service(client.clone(),host.clone())).unwrap();
+    assert_eq!(service["service"][0]["name"],Value::from("smb"));
+    success(run(db::del_service(client.clone(),host.clone())));
+
+    success(run(db::report_vuln(client.clone(),options(&[("host","10.0.0.5"),("name","MS17-010")]))));
+    let vulns:Value=run(db::vulns(client.clone(),VulnQuery::new().address("10.0.0.5"))).unwrap();
+    assert!(vulns["vulns"][0]["refs"].as_str().unwrap().contains("CVE-2017-0144"));
+    let vuln:Value=run(db::get_vuln(client.clone(),host.clone())).unwrap();
+    assert_eq!(vuln["vuln"][0]["port"],Value::from(445));
+    success(run(db::del_vuln(client.clone(),host.clone())));
+
+    success(run(db::report_note(client.clone(),options(&[("host","10.0.0.5"),("type","smb.fingerprint")]))));
+    let notes:Value=run(db::notes(client.clone(),NoteQuery::new().address("10.0.0.5"))).unwrap();
+    assert_eq!(notes["notes"][0]["type"],Value::from("smb.fingerprint"));
+    let note:Value=run(db::get_note(client.clone(),host.clone())).unwrap();
+    assert_eq!(note["note"][0]["service"],Value::from("smb"));
+    success(run(db::del_note(client.clone(),host.clone())));
+
+    let browser=options(&[("host","10.0.0.7")]);
+    success(run(db::report_client(client.clone(),options(&[("host","10.0.0.7"),("ua_string","Mozilla/5.0")]))));
+    let clients:Value=run(db::clients(client.clone(),ClientQuery::new().address("10.0.0.7"))).unwrap();
+    assert_eq!(clients["clients"][0]["ua_name"],Value::from("Firefox"));
+    let web_client:Value=run(db::get_client(client.clone(),browser.clone())).unwrap();
+    assert_eq!(web_client["client"][0]["host"],Value::from("10.0.0.7"));
+    success(run(db::del_client(client.clone(),browser)));
+
+    success(run(db::report_event(client.clone(),options(&[("host","10.0.0.5"),("name","module_run")]))));
+    let events:Value=run(db::events(client.clone(),ListQuery::new())).unwrap();
+    assert_eq!(events["events"][0]["name"],Value::from("module_run"));
+    success(run(db::report_loot(client.clone(),options(&[("host","10.0.0.5"),("ltype","windows.hashes")]))));
+    let loots:Value=run(db::loots(client.clone(),ListQuery::new())).unwrap();
+    assert_eq!(loots["loots"][0]["ltype"],Value::from("windows.hashes"));
+    let creds:Value=run(db::creds(client.clone(),ListQuery::new())).unwrap();
+    assert_eq!(creds["creds"][0]["user"],Value::from("Administrator"));
+    let reference:Value=run(db::get_ref(client.clone(),"CVE-2017-0144")).unwrap();
+    assert_eq!(reference["name"],Value::from("CVE-2017-0144"));
+
+    let calls=server.calls();
+    let report=calls.iter().find(|call| call[0]==Value::from("db.report_service")).unwrap();
+    assert_eq!(report[2]["port"],Value::from("445"));
+}
+
+#[test]
+fn db_connection_and_import() {
+    let (server,client)=setup();
+    let status:Value=run(db::status(client.clone())).unwrap();
+    assert_eq!(status["driver"],Value::from("postgresql"));
+    let driver:Value=run(db::get_driver(client.clone())).unwrap();
+    assert_eq!(driver["driver"],Value::from("postgresql"));
+    success(run(db::set_driver(client.clone(),"postgresql")));
+    success(run(db::dbconnect(client.clone(),"postgresql",options(&[("database","msf"),("username","msf")]))));
+    success(run(db::disconnect(client.clone())));
+
+    let data="<nmaprun></nmaprun>";
+    success(run(db::import_data(client.clone(),data)));
+    let path=std::env::temp_dir().join(format!("rust-metasploit-import-{}.xml",std::process::id()));
+    std::fs::write(&path,data).unwrap();
+    success(run(db::import_file(client,std::fs::File::open(&path).unwrap())));
+    std::fs::remove_file(&path).unwrap();
+
+    let calls=server.calls();
+    let connect=calls.iter().find(|call| call[0]==Value::from("db.connect")).unwrap();
+    assert_eq!(connect[2]["driver"],Value::from("postgresql"));
+    let imports:Vec<&Vec<Value>>=calls.iter().filter(|call| call[0]==Value::from("db.import_data")).collect();
+    assert_eq!(imports.len(),2);
+    assert!(imports.iter().all(|call| call[2]==Value::from(data)));
+}
+
+#[test]
+fn canned_replies() {
+    let (server,client)=setup();
+    server.respond("core.version",Value::Map(vec![(Value::from("version"),Value::from("6.4.0"))]));
+    let version:Value=run(core::version(client.clone())).unwrap();
+    assert_eq!(version["version"],Value::from("6.4.0"));
+    server.respond_error("db.status",500,"Database not connected");
+    msf_error(run(db::status::<Res>(client.clone())),"Database not connected");
+    msf_error(client.call::<Res>("core.nothing",vec![]),"Unknown API Call: 'core.nothing'");
+}
+
+#[test]
+fn error_kinds() {
+    let (server,client)=setup();
+    let kind=|reply:Result<Res,Error>| reply.unwrap_err().msf_kind();
+    assert_eq!(kind(client.call("core.nothing",vec![])),Some(MsfErrorKind::UnknownMethod));
+    assert_eq!(kind(run(modules::info(client.clone(),"exploit","windows/nothing"))),Some(MsfErrorKind::ModuleNotFound));
+    assert_eq!(kind(run(sessions::stop(client.clone(),"9"))),Some(MsfErrorKind::InvalidSession));
+    assert_eq!(kind(run(jobs::info(client.clone(),"9"))),Some(MsfErrorKind::JobNotFound));
+    for method in ["module.nothing","session.nothing","job.nothing"].iter() {
+        assert_eq!(kind(client.call(method,vec![Value::from("9")])),Some(MsfErrorKind::UnknownMethod),"{}",method);
+    }
+    let replies=[
+        ("Invalid Module",MsfErrorKind::ModuleNotFound),
+        ("Unknown Session ID 9",MsfErrorKind::InvalidSession),
+        ("Session is not of type Meterpreter",MsfErrorKind::InvalidSession),
+        ("Invalid Job",MsfErrorKind::JobNotFound),
+        ("Unknown API Call: 'module.nothing'",MsfErrorKind::UnknownMethod),
+        ("Module not loaded: invalid session handler",MsfErrorKind::ServerError),
+    ];
+    for (message,expected) in replies.iter() {
+        server.respond_error("core.stop",500,message);
+        assert_eq!(kind(run(core::stop(client.clone()))),Some(*expected),"{}",message);
+    }
+    server.respond_error("core.version",500,"undefined method `version' for nil:NilClass");
+    let error=run(core::version::<Res>(client.clone())).unwrap_err();
+    assert_eq!((error.msf_kind(),error.error_code()),(Some(MsfErrorKind::ServerError),Some(500)));
+    assert_eq!(format!("{:#}",error),"undefined method `version' for nil:NilClass (Msf::RPC::Exception,500)");
+    server.expire_tokens();
+    let error=run(core::save::<Res>(client)).unwrap_err();
+    assert_eq!(error.msf_kind(),Some(MsfErrorKind::InvalidToken));
+    assert_eq!(error.to_string(),"Invalid Authentication Token");
+}
@@ -0,0 +1,259 @@
+//! Logging in and calling through local stand-ins for an HTTP proxy and a SOCKS5 proxy (an SSH dynamic forward).
+use metasploit::client::{Client,ClientBuilder};
+use metasploit::error::Error;
+use metasploit::msf::core;
+use metasploit::value::Value;
+use std::io::{BufRead,BufReader,Read,Write};
+use std::net::{Shutdown,TcpListener,TcpStream};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize,Ordering};
+use std::thread;
+
+const BASIC_JUMP_SECRET:&str="Basic anVtcDpzZWNyZXQ=";
+
+/// Reads one HTTP message,returning its start line and headers and its body.
+fn read_message<R:BufRead>(reader:&mut R) -> Option<(Vec<String>,Vec<u8>)> {
+    let mut head=Vec::new();
+    let mut length=0;
+    loop {
+        let mut line=String::new();
+        if reader.read_line(&mut line).ok()?==0 {
+            return None;
+        }
+        let line=line.trim_end().to_string();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name,value))=line.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                length=value.trim().parse().ok()?;
+            }
+        }
+        head.push(line);
+    }
+    let mut body=vec![0;length];
+    reader.read_exact(&mut body).ok()?;
+    Some((head,body))
+}
+
+fn header<'a>(head:&'a [String],name:&str) -> Option<&'a str> {
+    head.iter().filter_map(|line| line.split_once(':')).find(|(n,_)| n.eq_ignore_ascii_case(name)).map(|(_,v)| v.trim())
+}
+
+fn respond(stream:&mut TcpStream,status:&str,body:&[u8]) {
+    let mut response=format!("HTTP/1.1 {}\r\nContent-Type: binary/message-pack\r\nContent-Length: {}\r\n\r\n",status,body.len()).into_bytes();
+    response.extend_from_slice(body);
+    stream.write_all(&response).unwrap();
+}
+
+/// A msfrpcd stand-in answering `auth.login` and `core.version`.
+fn msfrpcd() -> u16 {
+    let listener=TcpListener::bind("127.0.0.1:0").unwrap();
+    let port=listener.local_addr().unwrap().port();
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            thread::spawn(move || {
+                let mut writer=stream.try_clone().unwrap();
+                let mut reader=BufReader::new(stream);
+                while let Some((_,body))=read_message(&mut reader) {
+                    let call:Vec<Value>=rmp_serde::from_slice(&body).unwrap();
+                    let reply=match call[0].as_str() {
+                        Some("auth.login") => vec![("result","success"),("token","TEMPproxy")],
+                        _ if call[1].as_str()==Some("TEMPproxy") => vec![("version","6.3.0"),("ruby","3.0.5"),("api","1.0")],
+                        _ => vec![("error_message","Invalid Authentication Token")],
+                    };
+                    let reply=Value::Map(reply.into_iter().map(|(k,v)| (Value::from(k),Value::from(v))).collect());
+                    respond(&mut writer,"200 OK",&rmp_serde::to_vec_named(&reply).unwrap());
+                }
+            });
+        }
+    });
+    port
+}
+
+/// A forward HTTP proxy which relays absolute-form requests,optionally requiring basic auth.
+fn http_proxy(auth:Option<&'static str>,relayed:Arc<AtomicUsize>) -> u16 {
+    let listener=TcpListener::bind("127.0.0.1:0").unwrap();
+    let port=listener.local_addr().unwrap().port();
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let relayed=relayed.clone();
+            thread::spawn(move || {
+                let mut writer=stream.try_clone().unwrap();
+                let mut reader=BufReader::new(stream);
+                while let Some((head,body))=read_message(&mut reader) {
+                    if auth.is_some() && header(&head,"proxy-authorization")!=auth {
+                        respond(&mut writer,"407 Proxy Authentication Required",b"");
+                        continue;
+                    }
+                    let mut parts=head[0].split(' ');
+                    let (method,target)=(parts.next().unwrap(),parts.next().unwrap());
+                    let url=reqwest::Url::parse(target).unwrap();
+                    let mut upstream=TcpStream::connect((url.host_str().unwrap(),url.port().unwrap())).unwrap();
+                    let mut request=format!("{} {} HTTP/1.1\r\n",method,url.path());
+                    for line in head[1..].iter().filter(|line| !line.to_ascii_lowercase().starts_with("proxy-")) {
+                        request.push_str(line);
+                        request.push_str("\r\n");
+                    }
+                    request.push_str("\r\n");
+                    let mut request=request.into_bytes();
+                    request.extend_from_slice(&body);
+                    upstream.write_all(&request).unwrap();
+                    let (_,reply)=read_message(&mut BufReader::new(upstream)).unwrap();
+                    relayed.fetch_add(1,Ordering::SeqCst);
+                    respond(&mut writer,"200 OK",&reply);
+                }
+            });
+        }
+    });
+    port
+}
+
+/// A SOCKS5 proxy supporting CONNECT,optionally requiring username/password auth.
+fn socks5_proxy(auth:Option<(&'static str,&'static str)>,relayed:Arc<AtomicUsize>) -> u16 {
+    let listener=TcpListener::bind("127.0.0.1:0").unwrap();
+    let port=listener.local_addr().unwrap().port();
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let relayed=relayed.clone();
+            thread::spawn(move || socks5_session(stream,auth,relayed));
+        }
+    });
+    port
+}
+
+fn socks5_session(mut stream:TcpStream,auth:Option<(&str,&str)>,relayed:Arc<AtomicUsize>) -> Option<()> {
+    let mut greeting=[0u8;2];
+    stream.read_exact(&mut greeting).ok()?;
+    let mut methods=vec![0u8;greeting[1] as usize];
+    stream.read_exact(&mut methods).ok()?;
+    let method=if auth.is_some() { 2 } else { 0 };
+    if !methods.contains(&method) {
+        stream.write_all(&[5,0xff]).ok()?;
+        return None;
+    }
+    stream.write_all(&[5,method]).ok()?;
+    if let Some((user,password))=auth {
+        let field=|stream:&mut TcpStream| -> Option<String> {
+            let mut len=[0u8;1];
+            stream.read_exact(&mut len).ok()?;
+            let mut value=vec![0u8;len[0] as usize];
+            stream.read_exact(&mut value).ok()?;
+            String::from_utf8(value).ok()
+        };
+        let mut version=[0u8;1];
+        stream.read_exact(&mut version).ok()?;
+        let (u,p)=(field(&mut stream)?,field(&mut stream)?);
+        if u!=user || p!=password {
+            stream.write_all(&[1,1]).ok()?;
+            return None;
+        }
+        stream.write_all(&[1,0]).ok()?;
+    }
+    let mut request=[0u8;4];
+    stream.read_exact(&mut request).ok()?;
+    let host=match request[3] {
+        1 => {
+            let mut ip=[0u8;4];
+            stream.read_exact(&mut ip).ok()?;
+            std::net::Ipv4Addr::from(ip).to_string()
+        },
+        3 => {
+            let mut len=[0u8;1];
+            stream.read_exact(&mut len).ok()?;
+            let mut name=vec![0u8;len[0] as usize];
+            stream.read_exact(&mut name).ok()?;
+            String::from_utf8(name).ok()?
+        },
+        _ => return None,
+    };
+    let mut port=[0u8;2];
+    stream.read_exact(&mut port).ok()?;
+    let upstream=TcpStream::connect((host.as_str(),u16::from_be_bytes(port))).ok()?;
+    stream.write_all(&[5,0,0,1,0,0,0,0,0,0]).ok()?;
+    relayed.fetch_add(1,Ordering::SeqCst);
+    let (mut client_read,mut upstream_write)=(stream.try_clone().ok()?,upstream.try_clone().ok()?);
+    thread::spawn(move || {
+        let _=std::io::copy(&mut client_read,&mut upstream_write);
+        let _=upstream_write.shutdown(Shutdown::Write);
+    });
+    let (mut upstream_read,mut client_write)=(upstream,stream);
+    let _=std::io::copy(&mut upstream_read,&mut client_write);
+    let _=client_write.shutdown(Shutdown::Write);
+    Some(())
+}
+
+#[cfg(not(feature="async"))]
+fn version(client:&Client) -> Result<Value,Error> {
+    core::version(client.clone())
+}
+#[cfg(feature="async")]
+fn version(client:&Client) -> Result<Value,Error> {
+    tokio::runtime::Runtime::new().unwrap().block_on(core::version(client.clone()))
+}
+
+fn builder(port:u16) -> ClientBuilder {
+    Client::builder().host("127.0.0.1").port(port).ssl(false)
+}
+
+#[test]
+fn http_proxy_carries_login_and_calls() {
+    let relayed=Arc::new(AtomicUsize::new(0));
+    let proxy=http_proxy(None,relayed.clone());
+    let client=builder(msfrpcd()).proxy(&format!("http://127.0.0.1:{}",proxy)).login("msf","password").unwrap();
+    assert_eq!(client.gettoken(),"TEMPproxy");
+    version(&client).unwrap();
+    assert_eq!(relayed.load(Ordering::SeqCst),2);
+}
+
+#[test]
+fn http_proxy_credentials() {
+    let relayed=Arc::new(AtomicUsize::new(0));
+    let proxy=format!("http://127.0.0.1:{}",http_proxy(Some(BASIC_JUMP_SECRET),relayed.clone()));
+    let server=msfrpcd();
+    match builder(server).proxy(&proxy).login("msf","password") {
+        Err(Error::ConnectionError(e)) => assert_eq!(e.status(),Some(reqwest::StatusCode::PROXY_AUTHENTICATION_REQUIRED)),
+        other => panic!("expected a ConnectionError,got {:?}",other.map(|client| client.gettoken())),
+    }
+    match builder(server).proxy(&proxy).proxy_auth("jump","wrong").login("msf","password") {
+        Err(Error::ConnectionError(e)) => assert_eq!(e.status().map(|status| status.as_u16()),Some(407)),
+        other => panic!("expected a ConnectionError,got {:?}",other.map(|client| client.gettoken())),
+    }
+    let client=builder(server).proxy(&proxy).proxy_auth("jump","secret").login("msf","password").unwrap();
+    version(&client).unwrap();
+    let proxy=proxy.replace("http://","http://jump:secret@");
+    builder(server).proxy(&proxy).login("msf","password").unwrap();
+    assert_eq!(relayed.load(Ordering::SeqCst),3);
+}
+
+#[test]
+fn socks5_proxy_carries_login_and_calls() {
+    let relayed=Arc::new(AtomicUsize::new(0));
+    let proxy=socks5_proxy(None,relayed.clone());
+    let client=builder(msfrpcd()).proxy(&format!("socks5h://127.0.0.1:{}",proxy)).login("msf","password").unwrap();
+    version(&client).unwrap();
+    assert!(relayed.load(Ordering::SeqCst)>=1);
+}
+
+#[test]
+fn socks5_proxy_credentials() {
+    let relayed=Arc::new(AtomicUsize::new(0));
+    let proxy=format!("socks5://127.0.0.1:{}",socks5_proxy(Some(("jump","secret")),relayed.clone()));
+    let server=msfrpcd();
+    match builder(server).proxy(&proxy).proxy_auth("jump","wrong").login("msf","password") {
+        Err(Error::ConnectionError(_)) => {},
+        other => panic!("expected a connection error,got {:?}",other.map(|client| client.gettoken())),
+    }
+    let client=builder(server).proxy(&proxy).proxy_auth("jump","secret").login("msf","password").unwrap();
+    version(&client).unwrap();
+    let proxy=proxy.replace("socks5://","socks5://jump:secret@");
+    builder(server).proxy(&proxy).login("msf","password").unwrap();
+}
+
+#[test]
+fn invalid_proxy_url() {
+    match builder(msfrpcd()).proxy("not a url").login("msf","password") {
+        Err(Error::ConfigError{source,..}) => assert!(source.is_some()),
+        other => panic!("expected a config error,got {:?}",other.map(|client| client.gettoken())),
+    }
+}
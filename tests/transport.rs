@@ -0,0 +1,131 @@
+//! Scripting replies with `MemoryTransport` instead of talking to msfrpcd.
+use metasploit::client::Client;
+use metasploit::error::Error;
+use metasploit::msf::{console,sessions};
+use metasploit::transport::MemoryTransport;
+use metasploit::value::Value;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+#[derive(Deserialize,Debug,PartialEq)]
+struct ConsoleRead {
+    data:String,
+    prompt:String,
+    busy:bool,
+}
+
+fn map(fields:Vec<(&str,Value)>) -> Value {
+    Value::Map(fields.into_iter().map(|(k,v)| (Value::from(k),v)).collect())
+}
+
+fn login(mock:&MemoryTransport,token:&str) {
+    mock.reply("auth.login",map(vec![("result",Value::from("success")),("token",Value::from(token))]));
+}
+
+#[cfg(not(feature="async"))]
+fn run<T>(call:T) -> T {
+    call
+}
+#[cfg(feature="async")]
+fn run<F:std::future::Future>(call:F) -> F::Output {
+    tokio::runtime::Runtime::new().unwrap().block_on(call)
+}
+
+#[test]
+fn scripted_sessions_and_console() {
+    let mock=MemoryTransport::new();
+    mock.reply("session.list",map(vec![("1",map(vec![("type",Value::from("meterpreter")),("tunnel_peer",Value::from("10.0.0.5:49152"))]))]));
+    mock.reply("console.read",map(vec![("data",Value::from("msf6 > ")),("prompt",Value::from("msf6 > ")),("busy",Value::from(false))]));
+    let client=Client::builder().transport(mock.clone()).token("TEST").unwrap();
+
+    let list:HashMap<String,HashMap<String,String>>=run(sessions::list(client.clone())).unwrap();
+    assert_eq!(list["1"]["type"],"meterpreter");
+    let read:ConsoleRead=run(console::read(client.clone(),"0")).unwrap();
+    assert_eq!(read,ConsoleRead{data:"msf6 > ".to_string(),prompt:"msf6 > ".to_string(),busy:false});
+
+    let requests=mock.requests();
+    assert_eq!(requests[0],vec![Value::from("session.list"),Value::from("TEST")]);
+    assert_eq!(requests[1],vec![Value::from("console.read"),Value::from("TEST"),Value::from("0")]);
+}
+
+#[test]
+fn scripted_error_and_missing_reply() {
+    let mock=MemoryTransport::new();
+    mock.reply_error("console.read",500,"Invalid Console ID");
+    let client=Client::builder().transport(mock.clone()).token("TEST").unwrap();
+    match run(console::read::<ConsoleRead>(client.clone(),"7")) {
+        Err(Error::MsfError(e)) => assert_eq!(e.error_message,"Invalid Console ID"),
+        other => panic!("expected an MsfError,got {:?}",other),
+    }
+    mock.reply_error("console.read",500,"Invalid Console ID");
+    match run(console::read::<Value>(client.clone(),"7")) {
+        Err(Error::MsfError(e)) => assert_eq!(e.error_code,Some(500)),
+        other => panic!("expected an MsfError,got {:?}",other),
+    }
+    match run(sessions::list::<Value>(client)) {
+        Err(Error::TransportError(_)) => {},
+        other => panic!("expected a TransportError,got {:?}",other),
+    }
+}
+
+#[test]
+fn expired_token_is_renewed() {
+    let mock=MemoryTransport::new();
+    login(&mock,"TEMP1");
+    mock.reply_error("session.list",401,"Invalid Authentication Token");
+    login(&mock,"TEMP2");
+    mock.reply("session.list",map(vec![]));
+    mock.reply("session.list",map(vec![]));
+    let client=Client::builder().transport(mock.clone()).reauthenticate(true).login("msf","password").unwrap();
+    let other=client.clone();
+
+    let list:HashMap<String,Value>=run(sessions::list(client)).unwrap();
+    assert!(list.is_empty());
+    assert_eq!(other.gettoken(),"TEMP2");
+    let _:HashMap<String,Value>=run(sessions::list(other)).unwrap();
+
+    let tokens:Vec<Value>=mock.requests().into_iter().map(|call| call[1].clone()).collect();
+    let expected=["msf","TEMP1","msf","TEMP2","TEMP2"];
+    assert_eq!(tokens,expected.iter().map(|token| Value::from(*token)).collect::<Vec<_>>());
+}
+
+#[test]
+fn decode_error_keeps_context() {
+    let mock=MemoryTransport::new();
+    mock.reply("console.read",map(vec![("data",Value::from(7)),("prompt",Value::from("msf6 > ")),("busy",Value::from(false))]));
+    mock.reply_raw("console.list",b"<html>502 Bad Gateway</html>".to_vec());
+    let client=Client::builder().transport(mock).token("TEST").unwrap();
+
+    let error=run(console::read::<ConsoleRead>(client.clone(),"0")).unwrap_err();
+    match &error {
+        Error::DError(e) => {
+            assert_eq!(e.method,"console.read");
+            assert_eq!(e.response["data"],Value::from(7));
+            assert!(e.error.to_string().contains("string"),"{}",e.error);
+        },
+        other => panic!("expected a DError,got {:?}",other),
+    }
+    let source=std::error::Error::source(&error).unwrap();
+    assert!(source.to_string().starts_with("invalid reply to console.read: "));
+    assert!(source.source().is_some());
+
+    match run(console::list::<HashMap<String,Value>>(client)) {
+        Err(Error::DError(e)) => assert_eq!(e.response,Value::Binary(b"<html>502 Bad Gateway</html>".to_vec())),
+        other => panic!("expected a DError,got {:?}",other),
+    }
+}
+
+#[test]
+fn custom_transport_skips_http_settings() {
+    let mock=MemoryTransport::new();
+    login(&mock,"TEMP1");
+    let client=Client::builder().url("msfrpcd").proxy("not a proxy").header("bad header","x").transport(mock).login("msf","password").unwrap();
+    assert_eq!((client.gettoken().as_str(),client.url.as_str()),("TEMP1","msfrpcd"));
+    match Client::builder().url("msfrpcd").token("TEST") {
+        Err(Error::ConfigError{message,source:Some(source)}) => {
+            assert_eq!(message,"invalid url msfrpcd");
+            assert_eq!(source.to_string(),"relative URL without a base");
+        },
+        other => panic!("expected a ConfigError,got {:?}",other.map(|client| client.url)),
+    }
+}